@@ -0,0 +1,413 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display};
+use core::ops::{Bound, Deref, RangeBounds};
+use core::ptr::addr_of;
+use core::slice;
+
+use rkyv::bytecheck::CheckBytes;
+use rkyv::munge::munge;
+use rkyv::rancor::{Fallible, Source};
+use rkyv::ser::{Allocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Archived, Deserialize, Place, Portable, Serialize};
+use smallvec::{Array, SmallVec};
+
+use crate::{Comparable, KeyValuePair, SmallOrdSet};
+
+/// An archived [`SmallOrdSet`](type.SmallOrdSet.html).
+///
+/// The elements are stored as a sorted slice, so lookups binary search the archived bytes
+/// directly without deserializing. When the archive is validated, the slice is checked to be in
+/// strictly ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{KeyValuePair, SmallOrdSet};
+/// use rkyv::rancor::Error;
+/// use rkyv::Archived;
+///
+/// type Map = SmallOrdSet<[KeyValuePair<String, u32>; 4]>;
+///
+/// let mut map = Map::new();
+/// map.insert_value("b".to_owned(), 2);
+/// map.insert_value("a".to_owned(), 1);
+///
+/// let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+/// let archived = rkyv::access::<Archived<Map>, Error>(&bytes).unwrap();
+///
+/// assert!(archived.contains_key("a"));
+/// assert_eq!(archived.get_value("b").map(|value| value.to_native()), Some(2));
+/// assert_eq!(archived.get_value("c"), None);
+/// ```
+///
+/// Archived primitives use endian-aware types such as `u32_le`, which cannot be borrowed as their
+/// native type. Wrap native keys in [`Native`](struct.Native.html) to look them up:
+///
+/// ```
+/// use small_ord_set::{Native, SmallOrdSet};
+/// use rkyv::rancor::Error;
+/// use rkyv::Archived;
+///
+/// type Set = SmallOrdSet<[u32; 4]>;
+///
+/// let set = Set::from_buf([9, 5, 1, 7]);
+///
+/// let bytes = rkyv::to_bytes::<Error>(&set).unwrap();
+/// let archived = rkyv::access::<Archived<Set>, Error>(&bytes).unwrap();
+///
+/// assert!(archived.contains(&Native(5u32)));
+/// assert!(!archived.contains(&Native(6u32)));
+/// assert_eq!(archived.get(&Native(7u32)).map(|n| n.to_native()), Some(7));
+/// assert_eq!(archived.range(Native(2u32)..Native(9)).len(), 2);
+/// ```
+///
+/// Validation fails if the elements are not in strictly ascending order:
+///
+/// ```
+/// use small_ord_set::{KeyValuePair, SmallOrdSet};
+/// use smallvec::smallvec;
+/// use rkyv::rancor::Error;
+/// use rkyv::Archived;
+///
+/// type Set = SmallOrdSet<[u32; 4]>;
+/// type Map = SmallOrdSet<[KeyValuePair<u32, u32>; 4]>;
+///
+/// let set = Set::from_vec_unchecked(smallvec![5, 1, 9]);
+/// let bytes = rkyv::to_bytes::<Error>(&set).unwrap();
+/// let err = rkyv::access::<Archived<Set>, Error>(&bytes).unwrap_err();
+/// assert!(err.to_string().contains("element at index 1 is not greater"));
+///
+/// let map = Map::from_vec_unchecked(smallvec![
+///     KeyValuePair { key: 1, value: 10 },
+///     KeyValuePair { key: 1, value: 20 },
+/// ]);
+/// let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+/// let err = rkyv::access::<Archived<Map>, Error>(&bytes).unwrap_err();
+/// assert!(err.to_string().contains("element at index 1 is not greater"));
+/// ```
+#[repr(transparent)]
+pub struct ArchivedSmallOrdSet<T> {
+    vec: ArchivedVec<T>,
+}
+
+/// An archived [`KeyValuePair`](struct.KeyValuePair.html).
+///
+/// Like `KeyValuePair`, comparisons of this type only look at the key.
+#[repr(C)]
+pub struct ArchivedKeyValuePair<K, V> {
+    /// The archived key, used for checking ordering and equality.
+    pub key: K,
+    /// The archived value.
+    pub value: V,
+}
+
+/// A native key used to look up elements of an archived set.
+///
+/// The archived elements are converted to `Q` and compared using its `Ord` implementation. This
+/// allows looking up archived primitives, such as `u32_le`, with their native type.
+#[derive(Copy, Clone, Debug)]
+pub struct Native<Q>(pub Q);
+
+impl<T> ArchivedSmallOrdSet<T> {
+    /// Get a slice containing the whole set in sorted order.
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+
+    /// The number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Construct an iterator over the set, in ascending order.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    /// Binary searches the set with a comparator function.
+    ///
+    /// See [`SmallOrdSet::find_by`](struct.SortedSet.html#method.find_by).
+    pub fn find_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+
+    /// Returns a reference to the element in the set, if any, for which the comparator function
+    /// returns `Ordering::Equal`.
+    pub fn get_by<F>(&self, f: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        match self.find_by(f) {
+            Ok(idx) => Some(&self.as_slice()[idx]),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns `true` if the set contains an element.
+    pub fn contains<Q>(&self, element: &Q) -> bool
+    where
+        Q: Comparable<T> + ?Sized,
+    {
+        self.get(element).is_some()
+    }
+
+    /// Returns a reference to the element in the set, if any, that is equal to the given value.
+    pub fn get<Q>(&self, element: &Q) -> Option<&T>
+    where
+        Q: Comparable<T> + ?Sized,
+    {
+        self.get_by(|probe| element.compare(probe).reverse())
+    }
+
+    /// Returns the slice of elements within the given range.
+    pub fn range<Q, R>(&self, range: R) -> &[T]
+    where
+        Q: Comparable<T> + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let slice = self.as_slice();
+        let start = match range.start_bound() {
+            Bound::Included(q) => slice.partition_point(|probe| q.compare(probe).is_gt()),
+            Bound::Excluded(q) => slice.partition_point(|probe| q.compare(probe).is_ge()),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(q) => slice.partition_point(|probe| q.compare(probe).is_ge()),
+            Bound::Excluded(q) => slice.partition_point(|probe| q.compare(probe).is_gt()),
+            Bound::Unbounded => slice.len(),
+        };
+        &slice[start..end.max(start)]
+    }
+}
+
+impl<K, V> ArchivedSmallOrdSet<ArchivedKeyValuePair<K, V>> {
+    /// Gets a reference to the archived value for a key in the map.
+    pub fn get_value<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.get_by(|probe| key.compare(&probe.key).reverse())
+            .map(|kvp| &kvp.value)
+    }
+
+    /// Returns `true` if the map contains a value for the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.get_value(key).is_some()
+    }
+}
+
+impl<Q, T> Comparable<T> for Native<Q>
+where
+    Q: Ord + for<'a> From<&'a T>,
+{
+    fn compare(&self, key: &T) -> Ordering {
+        Ord::cmp(&self.0, &Q::from(key))
+    }
+}
+
+impl<A> Archive for SmallOrdSet<A>
+where
+    A: Array,
+    A::Item: Archive,
+{
+    type Archived = ArchivedSmallOrdSet<Archived<A::Item>>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedSmallOrdSet { vec } = out);
+        ArchivedVec::resolve_from_slice(self.as_slice(), resolver, vec);
+    }
+}
+
+impl<A, S> Serialize<S> for SmallOrdSet<A>
+where
+    A: Array,
+    A::Item: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(self.as_slice(), serializer)
+    }
+}
+
+impl<A, D> Deserialize<SmallOrdSet<A>, D> for ArchivedSmallOrdSet<Archived<A::Item>>
+where
+    A: Array,
+    A::Item: Archive,
+    Archived<A::Item>: Deserialize<A::Item, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<SmallOrdSet<A>, D::Error> {
+        let mut vec = SmallVec::with_capacity(self.len());
+        for element in self.iter() {
+            vec.push(element.deserialize(deserializer)?);
+        }
+        Ok(SmallOrdSet::from_vec_unchecked(vec))
+    }
+}
+
+impl<K, V> Archive for KeyValuePair<K, V>
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedKeyValuePair<Archived<K>, Archived<V>>;
+    type Resolver = (K::Resolver, V::Resolver);
+
+    fn resolve(&self, (key, value): Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedKeyValuePair { key: out_key, value: out_value } = out);
+        self.key.resolve(key, out_key);
+        self.value.resolve(value, out_value);
+    }
+}
+
+impl<K, V, S> Serialize<S> for KeyValuePair<K, V>
+where
+    K: Serialize<S>,
+    V: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok((
+            self.key.serialize(serializer)?,
+            self.value.serialize(serializer)?,
+        ))
+    }
+}
+
+impl<K, V, D> Deserialize<KeyValuePair<K, V>, D> for ArchivedKeyValuePair<Archived<K>, Archived<V>>
+where
+    K: Archive,
+    V: Archive,
+    Archived<K>: Deserialize<K, D>,
+    Archived<V>: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<KeyValuePair<K, V>, D::Error> {
+        Ok(KeyValuePair {
+            key: self.key.deserialize(deserializer)?,
+            value: self.value.deserialize(deserializer)?,
+        })
+    }
+}
+
+// SAFETY: `ArchivedSmallOrdSet` is a transparent wrapper around the portable `ArchivedVec`.
+unsafe impl<T: Portable> Portable for ArchivedSmallOrdSet<T> {}
+
+// SAFETY: `ArchivedKeyValuePair` is `repr(C)` and its fields are portable.
+unsafe impl<K: Portable, V: Portable> Portable for ArchivedKeyValuePair<K, V> {}
+
+// SAFETY: the bytes are valid if they form a valid `ArchivedVec`, which has the same layout. The
+// ordering check only reads the elements once they are known to be valid.
+unsafe impl<T, C> CheckBytes<C> for ArchivedSmallOrdSet<T>
+where
+    ArchivedVec<T>: CheckBytes<C>,
+    T: Ord,
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        ArchivedVec::<T>::check_bytes(value.cast(), context)?;
+        let slice = (*value).as_slice();
+        for index in 1..slice.len() {
+            if slice[index - 1] >= slice[index] {
+                return Err(C::Error::new(UnsortedError { index }));
+            }
+        }
+        Ok(())
+    }
+}
+
+// SAFETY: `ArchivedKeyValuePair` is valid if both of its fields are valid.
+unsafe impl<K, V, C> CheckBytes<C> for ArchivedKeyValuePair<K, V>
+where
+    K: CheckBytes<C>,
+    V: CheckBytes<C>,
+    C: Fallible + ?Sized,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        K::check_bytes(addr_of!((*value).key), context)?;
+        V::check_bytes(addr_of!((*value).value), context)
+    }
+}
+
+impl<T: Debug> Debug for ArchivedSmallOrdSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Deref for ArchivedSmallOrdSet<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArchivedSmallOrdSet<T> {
+    type IntoIter = slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for ArchivedKeyValuePair<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {:?}", self.key, self.value)
+    }
+}
+
+impl<K: PartialEq, V> PartialEq for ArchivedKeyValuePair<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&self.key, &other.key)
+    }
+}
+
+impl<K: Eq, V> Eq for ArchivedKeyValuePair<K, V> {}
+
+impl<K: PartialOrd, V> PartialOrd for ArchivedKeyValuePair<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&self.key, &other.key)
+    }
+}
+
+impl<K: Ord, V> Ord for ArchivedKeyValuePair<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&self.key, &other.key)
+    }
+}
+
+#[derive(Debug)]
+struct UnsortedError {
+    index: usize,
+}
+
+impl Display for UnsortedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archived set element at index {} is not greater than the previous element",
+            self.index
+        )
+    }
+}
+
+// Public error types only implement `std::error::Error` when the `std` feature is enabled. This
+// private error is an exception: rancor requires `core::error::Error` for validation errors even
+// without `std`, and the `rkyv` feature already needs a compiler which provides it.
+impl core::error::Error for UnsortedError {}
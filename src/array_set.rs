@@ -0,0 +1,458 @@
+use core::fmt::{self, Debug, Display};
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, RangeBounds};
+use core::slice;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use smallvec::{self, Array, SmallVec};
+
+use crate::{Comparable, Entry, SmallOrdSet, SortedSet, SortedStorage};
+
+/// A set represented by a sorted `SmallVec` which never spills to the heap.
+///
+/// The set can hold at most `A::size()` elements. It is backed by
+/// [`InlineStorage`](struct.InlineStorage.html), so operations which would exceed this capacity
+/// return a [`CapacityError`](struct.CapacityError.html) containing the element instead of
+/// allocating.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::ArrayOrdSet;
+///
+/// let mut set = ArrayOrdSet::<[u32; 2]>::new();
+///
+/// assert_eq!(set.insert(1), Ok(true));
+/// assert_eq!(set.insert(2), Ok(true));
+/// assert_eq!(set.insert(2), Ok(false));
+/// assert_eq!(set.insert(3).unwrap_err().into_element(), 3);
+/// assert_eq!(set.as_slice(), &[1, 2]);
+/// ```
+pub struct ArrayOrdSet<A: Array> {
+    set: SortedSet<InlineStorage<A>>,
+}
+
+/// A [`SortedStorage`](trait.SortedStorage.html) which stores at most `A::size()` elements inline
+/// and never spills to the heap.
+///
+/// Inserting into a full `InlineStorage` panics, or returns a
+/// [`CapacityError`](struct.CapacityError.html) from `try_insert`.
+pub struct InlineStorage<A: Array> {
+    vec: SmallVec<A>,
+}
+
+/// The error returned when an element cannot be added to an [`ArrayOrdSet`] because it is full.
+///
+/// [`ArrayOrdSet`]: struct.ArrayOrdSet.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CapacityError<T> {
+    element: T,
+}
+
+impl<A: Array> ArrayOrdSet<A> {
+    /// Make a new, empty, `ArrayOrdSet`.
+    pub fn new() -> Self {
+        ArrayOrdSet::default()
+    }
+
+    /// Convert a `SmallOrdSet` into an `ArrayOrdSet`, moving its elements inline if necessary.
+    ///
+    /// If the set has more elements than can be stored inline, it is returned in an `Err`.
+    pub fn try_from_set(mut set: SmallOrdSet<A>) -> Result<Self, SmallOrdSet<A>> {
+        if set.len() > A::size() {
+            return Err(set);
+        }
+        set.storage.shrink_to_fit();
+        Ok(ArrayOrdSet {
+            set: SortedSet::from_storage_unchecked(InlineStorage { vec: set.storage }),
+        })
+    }
+
+    /// Get a reference to the underlying set.
+    pub fn as_set(&self) -> &SortedSet<InlineStorage<A>> {
+        &self.set
+    }
+
+    /// Convert into a `SmallOrdSet`, which may grow beyond the inline capacity.
+    pub fn into_set(self) -> SmallOrdSet<A> {
+        SmallOrdSet::from_vec_unchecked(self.set.storage.vec)
+    }
+
+    /// Get a slice containing the whole set in sorted order.
+    pub fn as_slice(&self) -> &[A::Item] {
+        self.set.as_slice()
+    }
+
+    /// The maximum number of elements the set can hold.
+    pub fn capacity(&self) -> usize {
+        A::size()
+    }
+
+    /// Remove all elements from the set.
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    /// Creates a draining iterator that removes the specified range in the set
+    /// and yields the removed items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the set.
+    pub fn drain<R>(&mut self, range: R) -> smallvec::Drain<'_, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.set.storage.vec.drain(range)
+    }
+
+    /// The number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Returns `true` if the set holds its maximum number of elements.
+    pub fn is_full(&self) -> bool {
+        self.set.len() >= A::size()
+    }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut A::Item) -> bool,
+    {
+        self.set.storage.vec.retain(f)
+    }
+
+    /// Construct an iterator over the set, in ascending order.
+    pub fn iter(&self) -> slice::Iter<'_, A::Item> {
+        self.set.iter()
+    }
+
+    /// Returns a reference to the first element in the set, if any. This element is always the
+    /// minimum of all elements in the set.
+    pub fn first(&self) -> Option<&A::Item> {
+        self.set.first()
+    }
+
+    /// Returns a reference to the last element in the set, if any. This element is always the
+    /// maximum of all elements in the set.
+    pub fn last(&self) -> Option<&A::Item> {
+        self.set.last()
+    }
+}
+
+impl<A> ArrayOrdSet<A>
+where
+    A: Array,
+    A::Item: Ord,
+{
+    /// Constructs a new `ArrayOrdSet` from an `A` without copying elements.
+    pub fn from_buf(buf: A) -> Self {
+        let mut set = SortedSet::from_storage_unchecked(InlineStorage { vec: buf.into() });
+        set.sort_and_dedup();
+        ArrayOrdSet { set }
+    }
+
+    /// Adds an element to the set.
+    ///
+    /// Returns `Ok(true)` if the element was inserted, and `Ok(false)` if it was already present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error containing the element if it is not present and the set is full.
+    pub fn insert(&mut self, element: A::Item) -> Result<bool, CapacityError<A::Item>> {
+        self.set.try_insert(element)
+    }
+
+    /// Adds a element to the set, replacing the existing element, if any, that is equal to the
+    /// given one. Returns the replaced element.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error containing the element if it is not present and the set is full.
+    pub fn replace(&mut self, element: A::Item) -> Result<Option<A::Item>, CapacityError<A::Item>> {
+        self.set.try_replace(element)
+    }
+
+    /// Adds all elements of the iterator to the set, stopping at the first element which does not
+    /// fit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error containing the first element which could not be added. Elements before it
+    /// remain in the set, and elements after it are not consumed.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), CapacityError<A::Item>>
+    where
+        I: IntoIterator<Item = A::Item>,
+    {
+        for element in iter {
+            self.insert(element)?;
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the element in the set, if any, that is equal to the given one.
+    pub fn remove<Q>(&mut self, element: &Q) -> Option<A::Item>
+    where
+        Q: Comparable<A::Item> + ?Sized,
+    {
+        self.set.remove(element)
+    }
+
+    /// Returns `true` if the set contains an element.
+    pub fn contains<Q>(&self, element: &Q) -> bool
+    where
+        Q: Comparable<A::Item> + ?Sized,
+    {
+        self.set.contains(element)
+    }
+
+    /// Returns a reference to the element in the set, if any, that is equal to the given value.
+    pub fn get<Q>(&self, element: &Q) -> Option<&A::Item>
+    where
+        Q: Comparable<A::Item> + ?Sized,
+    {
+        self.set.get(element)
+    }
+
+    /// Returns a mutable reference to the element in the set, if any, that is equal to the given
+    /// value. It is an error to mutate the element such that its ordering changes.
+    pub fn get_mut<Q>(&mut self, element: &Q) -> Option<&mut A::Item>
+    where
+        Q: Comparable<A::Item> + ?Sized,
+    {
+        self.set.get_mut(element)
+    }
+
+    /// Gets the given key's corresponding entry in the set for in-place manipulation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error containing the key if it is not present and the set is full, so that a
+    /// vacant entry can always be inserted without spilling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{ArrayOrdSet, KeyValuePair};
+    ///
+    /// let mut counts = ArrayOrdSet::<[KeyValuePair<char, u32>; 2]>::new();
+    ///
+    /// *counts.entry('a').unwrap().or_insert(0) += 1;
+    /// *counts.entry('b').unwrap().or_insert(0) += 1;
+    /// *counts.entry('a').unwrap().or_insert(0) += 1;
+    /// assert_eq!(counts.entry('c').unwrap_err().into_element(), 'c');
+    /// ```
+    pub fn entry<Q>(&mut self, key: Q) -> Result<Entry<'_, InlineStorage<A>, Q>, CapacityError<Q>>
+    where
+        Q: Comparable<A::Item>,
+    {
+        match self.set.find(&key) {
+            Ok(idx) => Ok(Entry::occupied(&mut self.set, idx)),
+            Err(_) if self.is_full() => Err(CapacityError::new(key)),
+            Err(idx) => Ok(Entry::vacant(&mut self.set, idx, key)),
+        }
+    }
+}
+
+impl<A: Array> InlineStorage<A> {
+    /// Convert into the inner `SmallVec`, which is guaranteed not to have spilled.
+    pub fn into_inner(self) -> SmallVec<A> {
+        self.vec
+    }
+}
+
+impl<A: Array> SortedStorage for InlineStorage<A> {
+    type Item = A::Item;
+    type Error = CapacityError<A::Item>;
+
+    fn as_slice(&self) -> &[Self::Item] {
+        &self.vec
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+        &mut self.vec
+    }
+
+    fn insert(&mut self, index: usize, element: Self::Item) {
+        if self.try_insert(index, element).is_err() {
+            panic!("insufficient capacity")
+        }
+    }
+
+    fn try_insert(&mut self, index: usize, element: Self::Item) -> Result<(), Self::Error> {
+        if self.vec.len() >= A::size() {
+            return Err(CapacityError::new(element));
+        }
+        self.vec.insert(index, element);
+        Ok(())
+    }
+
+    fn remove(&mut self, index: usize) -> Self::Item {
+        self.vec.remove(index)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len)
+    }
+}
+
+impl<T> CapacityError<T> {
+    pub(crate) fn new(element: T) -> Self {
+        CapacityError { element }
+    }
+
+    /// Take ownership of the element which could not be added.
+    pub fn into_element(self) -> T {
+        self.element
+    }
+}
+
+impl<A> Clone for ArrayOrdSet<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        ArrayOrdSet {
+            set: self.set.clone(),
+        }
+    }
+}
+
+impl<A> Debug for ArrayOrdSet<A>
+where
+    A: Array,
+    A::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.set, f)
+    }
+}
+
+impl<A: Array> Default for ArrayOrdSet<A> {
+    fn default() -> Self {
+        ArrayOrdSet {
+            set: SortedSet::default(),
+        }
+    }
+}
+
+impl<A: Array> Deref for ArrayOrdSet<A> {
+    type Target = [A::Item];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<A> Eq for ArrayOrdSet<A>
+where
+    A: Array,
+    A::Item: Eq,
+{
+}
+
+impl<A> From<A> for ArrayOrdSet<A>
+where
+    A: Array,
+    A::Item: Ord,
+{
+    fn from(buf: A) -> Self {
+        ArrayOrdSet::from_buf(buf)
+    }
+}
+
+impl<A> Hash for ArrayOrdSet<A>
+where
+    A: Array,
+    A::Item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.set.hash(state)
+    }
+}
+
+impl<A: Array> IntoIterator for ArrayOrdSet<A> {
+    type IntoIter = smallvec::IntoIter<A>;
+    type Item = A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.storage.vec.into_iter()
+    }
+}
+
+impl<'a, A: Array> IntoIterator for &'a ArrayOrdSet<A> {
+    type IntoIter = slice::Iter<'a, A::Item>;
+    type Item = &'a A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<A> PartialEq for ArrayOrdSet<A>
+where
+    A: Array,
+    A::Item: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&self.set, &other.set)
+    }
+}
+
+impl<A> Clone for InlineStorage<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        InlineStorage {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<A> Debug for InlineStorage<A>
+where
+    A: Array,
+    A::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.vec, f)
+    }
+}
+
+impl<A: Array> Default for InlineStorage<A> {
+    fn default() -> Self {
+        InlineStorage {
+            vec: SmallVec::new(),
+        }
+    }
+}
+
+impl<A: Array> IntoIterator for InlineStorage<A> {
+    type IntoIter = smallvec::IntoIter<A>;
+    type Item = A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+impl<T> Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug> Error for CapacityError<T> {}
@@ -0,0 +1,379 @@
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+
+use smallvec::SmallVec;
+
+use crate::{Comparable, KeyValuePair, SmallOrdSet};
+
+/// A bidirectional map between unique left and right values, represented by a `SmallVec` of
+/// pairs sorted by left value and an index of those pairs sorted by right value.
+///
+/// Each left value and each right value appears in at most one pair, so the map can be queried
+/// efficiently from either side.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::SmallOrdBiMap;
+///
+/// let mut codes = SmallOrdBiMap::<&str, u8, 4>::new();
+/// codes.insert("get", 1);
+/// codes.insert("put", 2);
+///
+/// assert_eq!(codes.get_by_left("put"), Some(&2));
+/// assert_eq!(codes.get_by_right(&1), Some(&"get"));
+///
+/// assert_eq!(codes.remove_by_right(&2), Some(("put", 2)));
+/// assert_eq!(codes.get_by_left("put"), None);
+/// ```
+pub struct SmallOrdBiMap<L, R, const N: usize> {
+    pairs: SmallOrdSet<[KeyValuePair<L, R>; N]>,
+    by_right: SmallVec<[usize; N]>,
+}
+
+/// The pairs overwritten by a call to [`SmallOrdBiMap::insert`].
+///
+/// [`SmallOrdBiMap::insert`]: struct.SmallOrdBiMap.html#method.insert
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Overwritten<L, R> {
+    /// Neither the left nor the right value was present in the map.
+    Neither,
+    /// The left value was present in the map, paired with the returned right value.
+    Left(L, R),
+    /// The right value was present in the map, paired with the returned left value.
+    Right(L, R),
+    /// The exact pair was already present in the map.
+    Pair(L, R),
+    /// The left and right values were present in the map, in two different pairs. The pair
+    /// containing the left value is returned first.
+    Both((L, R), (L, R)),
+}
+
+impl<L, R, const N: usize> SmallOrdBiMap<L, R, N> {
+    /// Make a new, empty, `SmallOrdBiMap`.
+    pub fn new() -> Self {
+        SmallOrdBiMap::default()
+    }
+
+    /// Remove all pairs from the map.
+    pub fn clear(&mut self) {
+        self.pairs.clear();
+        self.by_right.clear();
+    }
+
+    /// The number of pairs in the map.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns `true` if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Get an iterator over all pairs in the map, in ascending order of left value.
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> + Clone {
+        self.pairs.iter().map(|kvp| (&kvp.key, &kvp.value))
+    }
+
+    /// Get an iterator over all pairs in the map, in ascending order of right value.
+    pub fn iter_by_right(&self) -> impl Iterator<Item = (&L, &R)> + Clone {
+        self.by_right.iter().map(move |&idx| {
+            let kvp = &self.pairs[idx];
+            (&kvp.key, &kvp.value)
+        })
+    }
+
+    /// Get an iterator over all left values in the map, in ascending order.
+    pub fn left_values(&self) -> impl Iterator<Item = &L> + Clone {
+        self.pairs.iter().map(|kvp| &kvp.key)
+    }
+
+    /// Get an iterator over all right values in the map, in ascending order.
+    pub fn right_values(&self) -> impl Iterator<Item = &R> + Clone {
+        self.by_right.iter().map(move |&idx| &self.pairs[idx].value)
+    }
+}
+
+impl<L, R, const N: usize> SmallOrdBiMap<L, R, N>
+where
+    L: Ord,
+    R: Ord,
+{
+    /// Inserts a pair into the map, removing any existing pairs containing either value.
+    ///
+    /// Returns the pairs which were removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{Overwritten, SmallOrdBiMap};
+    ///
+    /// let mut map = SmallOrdBiMap::<char, u32, 4>::new();
+    ///
+    /// assert_eq!(map.insert('a', 1), Overwritten::Neither);
+    /// assert_eq!(map.insert('b', 2), Overwritten::Neither);
+    /// assert_eq!(map.insert('a', 3), Overwritten::Left('a', 1));
+    /// assert_eq!(map.insert('c', 2), Overwritten::Right('b', 2));
+    /// assert_eq!(map.insert('c', 2), Overwritten::Pair('c', 2));
+    /// assert_eq!(map.insert('a', 2), Overwritten::Both(('a', 3), ('c', 2)));
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        let overwritten = match (self.remove_by_left(&left), self.remove_by_right(&right)) {
+            (None, None) => Overwritten::Neither,
+            (Some((l, r)), None) if r == right => Overwritten::Pair(l, r),
+            (Some((l, r)), None) => Overwritten::Left(l, r),
+            (None, Some((l, r))) => Overwritten::Right(l, r),
+            (Some(a), Some(b)) => Overwritten::Both(a, b),
+        };
+        self.insert_unchecked(left, right);
+        overwritten
+    }
+
+    /// Inserts a pair into the map, if neither value is already present.
+    ///
+    /// If either value is already present, the map is not modified and the pair is returned in
+    /// an `Err`.
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            return Err((left, right));
+        }
+        self.insert_unchecked(left, right);
+        Ok(())
+    }
+
+    /// Gets a reference to the right value paired with the given left value.
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        Q: Comparable<L> + ?Sized,
+    {
+        self.pairs.get_value(left)
+    }
+
+    /// Gets a reference to the left value paired with the given right value.
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        Q: Comparable<R> + ?Sized,
+    {
+        match self.find_right(right) {
+            Ok(pos) => Some(&self.pairs[self.by_right[pos]].key),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a pair with the given left value.
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        Q: Comparable<L> + ?Sized,
+    {
+        self.pairs.contains_key(left)
+    }
+
+    /// Returns `true` if the map contains a pair with the given right value.
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        Q: Comparable<R> + ?Sized,
+    {
+        self.find_right(right).is_ok()
+    }
+
+    /// Removes and returns the pair with the given left value, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdBiMap;
+    ///
+    /// let mut map: SmallOrdBiMap<char, u32, 4> = vec![('a', 3), ('b', 1), ('c', 4), ('d', 2)]
+    ///     .into_iter()
+    ///     .collect();
+    ///
+    /// assert_eq!(map.remove_by_left(&'b'), Some(('b', 1)));
+    /// assert_eq!(map.remove_by_left(&'b'), None);
+    ///
+    /// assert_eq!(
+    ///     map.iter_by_right().collect::<Vec<_>>(),
+    ///     [(&'d', &2), (&'a', &3), (&'c', &4)],
+    /// );
+    /// assert_eq!(map.get_by_right(&1), None);
+    /// assert_eq!(map.get_by_right(&2), Some(&'d'));
+    /// assert_eq!(map.get_by_right(&4), Some(&'c'));
+    /// ```
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        Q: Comparable<L> + ?Sized,
+    {
+        let idx = self
+            .pairs
+            .find_by(|probe| left.compare(&probe.key).reverse())
+            .ok()?;
+        let pos = self
+            .find_right(&self.pairs[idx].value)
+            .expect("right index is out of sync");
+        Some(self.remove_at(idx, pos))
+    }
+
+    /// Removes and returns the pair with the given right value, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdBiMap;
+    ///
+    /// let mut map: SmallOrdBiMap<char, u32, 4> = vec![('a', 3), ('b', 1), ('c', 4), ('d', 2)]
+    ///     .into_iter()
+    ///     .collect();
+    ///
+    /// assert_eq!(map.remove_by_right(&3), Some(('a', 3)));
+    /// assert_eq!(map.remove_by_right(&3), None);
+    ///
+    /// assert_eq!(
+    ///     map.iter_by_right().collect::<Vec<_>>(),
+    ///     [(&'b', &1), (&'d', &2), (&'c', &4)],
+    /// );
+    /// assert_eq!(map.get_by_left(&'a'), None);
+    /// assert_eq!(map.get_by_right(&1), Some(&'b'));
+    /// assert_eq!(map.get_by_right(&4), Some(&'c'));
+    /// ```
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        Q: Comparable<R> + ?Sized,
+    {
+        let pos = self.find_right(right).ok()?;
+        Some(self.remove_at(self.by_right[pos], pos))
+    }
+
+    fn insert_unchecked(&mut self, left: L, right: R) {
+        let idx = self
+            .pairs
+            .find_by(|probe| Ord::cmp(&probe.key, &left))
+            .unwrap_err();
+        let pos = self.find_right(&right).unwrap_err();
+        for other in &mut self.by_right {
+            if *other >= idx {
+                *other += 1;
+            }
+        }
+        self.pairs.storage.insert(
+            idx,
+            KeyValuePair {
+                key: left,
+                value: right,
+            },
+        );
+        self.by_right.insert(pos, idx);
+    }
+
+    fn remove_at(&mut self, idx: usize, pos: usize) -> (L, R) {
+        self.by_right.remove(pos);
+        for other in &mut self.by_right {
+            if *other > idx {
+                *other -= 1;
+            }
+        }
+        let kvp = self.pairs.storage.remove(idx);
+        (kvp.key, kvp.value)
+    }
+
+    fn find_right<Q>(&self, right: &Q) -> Result<usize, usize>
+    where
+        Q: Comparable<R> + ?Sized,
+    {
+        let pairs = &self.pairs;
+        self.by_right
+            .binary_search_by(|&idx| right.compare(&pairs[idx].value).reverse())
+    }
+}
+
+impl<L, R, const N: usize> Clone for SmallOrdBiMap<L, R, N>
+where
+    L: Clone,
+    R: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallOrdBiMap {
+            pairs: self.pairs.clone(),
+            by_right: self.by_right.clone(),
+        }
+    }
+}
+
+impl<L, R, const N: usize> Debug for SmallOrdBiMap<L, R, N>
+where
+    L: Debug,
+    R: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<L, R, const N: usize> Default for SmallOrdBiMap<L, R, N> {
+    fn default() -> Self {
+        SmallOrdBiMap {
+            pairs: SmallOrdSet::default(),
+            by_right: SmallVec::new(),
+        }
+    }
+}
+
+impl<L: Eq, R: Eq, const N: usize> Eq for SmallOrdBiMap<L, R, N> {}
+
+impl<L, R, const N: usize> Extend<(L, R)> for SmallOrdBiMap<L, R, N>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        for (left, right) in iter {
+            self.insert(left, right);
+        }
+    }
+}
+
+impl<L, R, const N: usize> FromIterator<(L, R)> for SmallOrdBiMap<L, R, N>
+where
+    L: Ord,
+    R: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (L, R)>,
+    {
+        let mut map = SmallOrdBiMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<L: Hash, R: Hash, const N: usize> Hash for SmallOrdBiMap<L, R, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for (left, right) in self.iter() {
+            left.hash(state);
+            right.hash(state);
+        }
+    }
+}
+
+impl<L, R, const N: usize> PartialEq for SmallOrdBiMap<L, R, N>
+where
+    L: PartialEq,
+    R: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<L, R> Overwritten<L, R> {
+    /// Returns `true` if any pairs were overwritten.
+    pub fn did_overwrite(&self) -> bool {
+        !matches!(self, Overwritten::Neither)
+    }
+}
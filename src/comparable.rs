@@ -0,0 +1,45 @@
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+
+/// Key ordering trait, used to look up elements of a `SmallOrdSet` by a value of a different type.
+///
+/// This trait is implemented for every `Q: Ord` which is a borrowed form of `K`, so any type
+/// which could be used with `Borrow` can be used for lookups. It can also be implemented for
+/// types which cannot be borrowed from the element type, such as composite keys containing
+/// borrowed data.
+///
+/// The ordering *must* be consistent with the ordering of `K`.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use small_ord_set::{Comparable, SmallOrdSet};
+///
+/// struct Query<'a>(&'a str, u32);
+///
+/// impl Comparable<(String, u32)> for Query<'_> {
+///     fn compare(&self, key: &(String, u32)) -> Ordering {
+///         (self.0, self.1).cmp(&(key.0.as_str(), key.1))
+///     }
+/// }
+///
+/// let set = SmallOrdSet::from_buf([("a".to_owned(), 1), ("b".to_owned(), 2)]);
+///
+/// assert!(set.contains(&Query("b", 2)));
+/// assert!(!set.contains(&Query("b", 1)));
+/// ```
+pub trait Comparable<K: ?Sized> {
+    /// Compares `self` to `key` and returns their ordering.
+    fn compare(&self, key: &K) -> Ordering;
+}
+
+impl<Q, K> Comparable<K> for Q
+where
+    Q: Ord + ?Sized,
+    K: Borrow<Q> + ?Sized,
+{
+    fn compare(&self, key: &K) -> Ordering {
+        Ord::cmp(self, key.borrow())
+    }
+}
@@ -0,0 +1,159 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+
+/// A total order over values of type `T`, used by [`SmallOrdSetBy`] in place of the `Ord` impl of
+/// its elements.
+///
+/// Implementations must define a [total order](https://doc.rust-lang.org/std/cmp/trait.Ord.html),
+/// otherwise the result of any operations on the set is unspecified.
+///
+/// [`SmallOrdSetBy`]: struct.SmallOrdSetBy.html
+pub trait Comparator<T: ?Sized> {
+    /// Returns the ordering between `a` and `b`.
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Orders values by their `Ord` implementation.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Natural;
+
+/// Orders values by the reverse of their `Ord` implementation.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Reverse;
+
+/// Orders values by a key extracted using the given function.
+///
+/// The key is returned by value, so it cannot borrow from the element. Use
+/// [`ByKeyRef`](struct.ByKeyRef.html) for keys which are borrowed.
+#[derive(Copy, Clone, Default)]
+pub struct ByKey<F>(pub F);
+
+/// Orders values by a key borrowed from them using the given function.
+///
+/// Closures should be passed to [`ByKeyRef::new`](struct.ByKeyRef.html#method.new), so that the
+/// returned reference is inferred to borrow from the element.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{ByKeyRef, SmallOrdSetBy};
+///
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let mut people = SmallOrdSetBy::<[Person; 4], _>::with_comparator(ByKeyRef::new(
+///     |person: &Person| person.name.as_str(),
+/// ));
+/// people.insert(Person { name: "carol".to_owned(), age: 40 });
+/// people.insert(Person { name: "alice".to_owned(), age: 30 });
+///
+/// let ages: Vec<u32> = people.iter().map(|person| person.age).collect();
+/// assert_eq!(ages, [30, 40]);
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct ByKeyRef<F>(pub F);
+
+/// Orders `f64` values by [`f64::total_cmp`](https://doc.rust-lang.org/std/primitive.f64.html#method.total_cmp).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TotalOrdF64;
+
+/// Orders `f32` values by [`f32::total_cmp`](https://doc.rust-lang.org/std/primitive.f32.html#method.total_cmp).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TotalOrdF32;
+
+/// Orders strings lexicographically, ignoring case.
+///
+/// Characters are compared by their lowercase mapping, so strings that differ only in case are
+/// considered equal.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CaseInsensitive;
+
+impl<T: Ord + ?Sized> Comparator<T> for Natural {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        Ord::cmp(a, b)
+    }
+}
+
+impl<T: Ord + ?Sized> Comparator<T> for Reverse {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        Ord::cmp(b, a)
+    }
+}
+
+impl<T, F, K> Comparator<T> for ByKey<F>
+where
+    T: ?Sized,
+    F: Fn(&T) -> K,
+    K: Ord,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        Ord::cmp(&(self.0)(a), &(self.0)(b))
+    }
+}
+
+impl<F> ByKeyRef<F> {
+    /// Make a new `ByKeyRef` from a function returning a key borrowed from its argument.
+    pub fn new<T, K>(f: F) -> Self
+    where
+        T: ?Sized,
+        K: ?Sized,
+        F: Fn(&T) -> &K,
+    {
+        ByKeyRef(f)
+    }
+}
+
+impl<T, F, K> Comparator<T> for ByKeyRef<F>
+where
+    T: ?Sized,
+    F: Fn(&T) -> &K,
+    K: Ord + ?Sized,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        Ord::cmp((self.0)(a), (self.0)(b))
+    }
+}
+
+impl Comparator<f64> for TotalOrdF64 {
+    fn compare(&self, a: &f64, b: &f64) -> Ordering {
+        a.total_cmp(b)
+    }
+}
+
+impl Comparator<f32> for TotalOrdF32 {
+    fn compare(&self, a: &f32, b: &f32) -> Ordering {
+        a.total_cmp(b)
+    }
+}
+
+impl<T: AsRef<str> + ?Sized> Comparator<T> for CaseInsensitive {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        let a = a.as_ref().chars().flat_map(char::to_lowercase);
+        let b = b.as_ref().chars().flat_map(char::to_lowercase);
+        Iterator::cmp(a, b)
+    }
+}
+
+impl<C, T> Comparator<T> for &C
+where
+    C: Comparator<T> + ?Sized,
+    T: ?Sized,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        C::compare(self, a, b)
+    }
+}
+
+impl<F> Debug for ByKey<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ByKey").finish()
+    }
+}
+
+impl<F> Debug for ByKeyRef<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ByKeyRef").finish()
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{KeyValuePair, SmallOrdSet};
+
+impl<T, const N: usize> SmallOrdSet<[T; N]> {
+    /// Make a new, empty, `SmallOrdSet`.
+    ///
+    /// This is a `const` version of [`new`](#method.new), which may be used to initialize a
+    /// `static` or `const` item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// static EMPTY: SmallOrdSet<[u32; 4]> = SmallOrdSet::new_const();
+    ///
+    /// assert!(EMPTY.is_empty());
+    /// ```
+    pub const fn new_const() -> Self {
+        SmallOrdSet {
+            storage: smallvec::SmallVec::new_const(),
+        }
+    }
+}
+
+macro_rules! impl_from_sorted_array {
+    ($($(#[$doc:meta])* [$($gen:tt)*] $t:ty => |$a:ident, $b:ident| $lt:expr;)*) => {$(
+        impl<$($gen)* const N: usize> SmallOrdSet<[$t; N]> {
+            /// Constructs a new `SmallOrdSet` from an array which is already sorted in strictly
+            /// ascending order, without copying elements.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the array is not strictly sorted. When used to initialize a `static` or
+            /// `const` item, this is a compile-time error.
+            $(#[$doc])*
+            pub const fn from_sorted_array(array: [$t; N]) -> Self {
+                let mut i = 1;
+                while i < N {
+                    let ($a, $b) = (&array[i - 1], &array[i]);
+                    if !$lt {
+                        panic!("array is not strictly sorted");
+                    }
+                    i += 1;
+                }
+                SmallOrdSet {
+                    storage: smallvec::SmallVec::from_const(array),
+                }
+            }
+        }
+
+        impl<$($gen)* V, const N: usize> SmallOrdSet<[KeyValuePair<$t, V>; N]> {
+            /// Constructs a new map from an array of key-value pairs whose keys are already sorted
+            /// in strictly ascending order, without copying elements.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the keys are not strictly sorted. When used to initialize a `static` or
+            /// `const` item, this is a compile-time error.
+            pub const fn from_sorted_array(array: [KeyValuePair<$t, V>; N]) -> Self {
+                let mut i = 1;
+                while i < N {
+                    let ($a, $b) = (&array[i - 1].key, &array[i].key);
+                    if !$lt {
+                        panic!("array is not strictly sorted");
+                    }
+                    i += 1;
+                }
+                SmallOrdSet {
+                    storage: smallvec::SmallVec::from_const(array),
+                }
+            }
+        }
+    )*};
+}
+
+impl_from_sorted_array! {
+    [] u8 => |a, b| *a < *b;
+    [] u16 => |a, b| *a < *b;
+    [] u32 => |a, b| *a < *b;
+    [] u64 => |a, b| *a < *b;
+    [] u128 => |a, b| *a < *b;
+    [] usize => |a, b| *a < *b;
+    [] i8 => |a, b| *a < *b;
+    [] i16 => |a, b| *a < *b;
+    [] i32 => |a, b| *a < *b;
+    [] i64 => |a, b| *a < *b;
+    [] i128 => |a, b| *a < *b;
+    [] isize => |a, b| *a < *b;
+    [] char => |a, b| (*a as u32) < (*b as u32);
+    [] bool => |a, b| !*a & *b;
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// static MIME_TYPES: SmallOrdSet<[&str; 3]> =
+    ///     SmallOrdSet::<[&str; 3]>::from_sorted_array(["application/json", "image/png", "text/plain"]);
+    ///
+    /// assert!(MIME_TYPES.contains("image/png"));
+    /// assert!(!MIME_TYPES.contains("image/gif"));
+    /// ```
+    ///
+    /// An unsorted array is rejected at compile time.
+    ///
+    /// ```compile_fail
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// static MIME_TYPES: SmallOrdSet<[&str; 2]> =
+    ///     SmallOrdSet::<[&str; 2]>::from_sorted_array(["text/plain", "image/png"]);
+    /// ```
+    ['a,] &'a str => |a, b| str_lt(a, b);
+}
+
+/// Compares strings lexicographically by bytes, which matches the `Ord` implementation for `str`.
+const fn str_lt(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+        i += 1;
+    }
+    a.len() < b.len()
+}
@@ -0,0 +1,384 @@
+use core::fmt::{self, Debug, Display};
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::{SortedSet, SortedStorage};
+
+/// A cursor over a `SortedSet`.
+///
+/// A cursor points to an element of the set, or to a "ghost" non-element which sits between the
+/// last and first elements. It can move freely in either direction, wrapping around through the
+/// ghost element.
+///
+/// This `struct` is constructed from the [`lower_bound`] and [`upper_bound`] methods on
+/// [`SortedSet`].
+///
+/// [`SortedSet`]: struct.SortedSet.html
+/// [`lower_bound`]: struct.SortedSet.html#method.lower_bound
+/// [`upper_bound`]: struct.SortedSet.html#method.upper_bound
+pub struct Cursor<'a, S: SortedStorage> {
+    set: &'a SortedSet<S>,
+    idx: usize,
+}
+
+/// A cursor over a `SortedSet` with editing operations.
+///
+/// A cursor points to an element of the set, or to a "ghost" non-element which sits between the
+/// last and first elements. It can move freely in either direction, wrapping around through the
+/// ghost element, and can insert or remove elements around its position.
+///
+/// This `struct` is constructed from the [`lower_bound_mut`] and [`upper_bound_mut`] methods on
+/// [`SortedSet`].
+///
+/// [`SortedSet`]: struct.SortedSet.html
+/// [`lower_bound_mut`]: struct.SortedSet.html#method.lower_bound_mut
+/// [`upper_bound_mut`]: struct.SortedSet.html#method.upper_bound_mut
+pub struct CursorMut<'a, S: SortedStorage> {
+    set: &'a mut SortedSet<S>,
+    idx: usize,
+}
+
+/// The error returned by [`CursorMut::insert_before`] and [`CursorMut::insert_after`] when the
+/// element would not be strictly between its neighbours.
+///
+/// [`CursorMut::insert_before`]: struct.CursorMut.html#method.insert_before
+/// [`CursorMut::insert_after`]: struct.CursorMut.html#method.insert_after
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnorderedElementError<T> {
+    element: T,
+}
+
+impl<'a, S: SortedStorage> Cursor<'a, S> {
+    pub(crate) fn new(set: &'a SortedSet<S>, idx: usize) -> Self {
+        Cursor { set, idx }
+    }
+
+    /// Returns the index of the element the cursor is pointing to, or `None` if it is pointing
+    /// to the ghost element.
+    pub fn index(&self) -> Option<usize> {
+        index(self.idx, self.set.len())
+    }
+
+    /// Returns a reference to the element the cursor is pointing to, or `None` if it is pointing
+    /// to the ghost element.
+    pub fn current(&self) -> Option<&'a S::Item> {
+        self.set.as_slice().get(self.idx)
+    }
+
+    /// Moves the cursor to the next element. If the cursor is pointing to the last element, it
+    /// moves to the ghost element, and if it is pointing to the ghost element, it moves to the
+    /// first element.
+    pub fn move_next(&mut self) {
+        self.idx = next(self.idx, self.set.len());
+    }
+
+    /// Moves the cursor to the previous element. If the cursor is pointing to the first element,
+    /// it moves to the ghost element, and if it is pointing to the ghost element, it moves to the
+    /// last element.
+    pub fn move_prev(&mut self) {
+        self.idx = prev(self.idx, self.set.len());
+    }
+
+    /// Returns a reference to the element after the one the cursor is pointing to, without
+    /// moving the cursor.
+    pub fn peek_next(&self) -> Option<&'a S::Item> {
+        self.set.as_slice().get(next(self.idx, self.set.len()))
+    }
+
+    /// Returns a reference to the element before the one the cursor is pointing to, without
+    /// moving the cursor.
+    pub fn peek_prev(&self) -> Option<&'a S::Item> {
+        self.set.as_slice().get(prev(self.idx, self.set.len()))
+    }
+}
+
+impl<'a, S: SortedStorage> CursorMut<'a, S> {
+    pub(crate) fn new(set: &'a mut SortedSet<S>, idx: usize) -> Self {
+        CursorMut { set, idx }
+    }
+
+    /// Returns the index of the element the cursor is pointing to, or `None` if it is pointing
+    /// to the ghost element.
+    pub fn index(&self) -> Option<usize> {
+        index(self.idx, self.set.len())
+    }
+
+    /// Returns a reference to the element the cursor is pointing to, or `None` if it is pointing
+    /// to the ghost element.
+    pub fn current(&self) -> Option<&S::Item> {
+        self.set.as_slice().get(self.idx)
+    }
+
+    /// Moves the cursor to the next element. If the cursor is pointing to the last element, it
+    /// moves to the ghost element, and if it is pointing to the ghost element, it moves to the
+    /// first element.
+    pub fn move_next(&mut self) {
+        self.idx = next(self.idx, self.set.len());
+    }
+
+    /// Moves the cursor to the previous element. If the cursor is pointing to the first element,
+    /// it moves to the ghost element, and if it is pointing to the ghost element, it moves to the
+    /// last element.
+    pub fn move_prev(&mut self) {
+        self.idx = prev(self.idx, self.set.len());
+    }
+
+    /// Returns a reference to the element after the one the cursor is pointing to, without
+    /// moving the cursor.
+    pub fn peek_next(&self) -> Option<&S::Item> {
+        self.set.as_slice().get(next(self.idx, self.set.len()))
+    }
+
+    /// Returns a reference to the element before the one the cursor is pointing to, without
+    /// moving the cursor.
+    pub fn peek_prev(&self) -> Option<&S::Item> {
+        self.set.as_slice().get(prev(self.idx, self.set.len()))
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    pub fn as_cursor(&self) -> Cursor<'_, S> {
+        Cursor::new(self.set, self.idx)
+    }
+
+    /// Removes the current element from the set and returns it. The cursor is then moved to
+    /// point to the next element.
+    ///
+    /// If the cursor is pointing to the ghost element, then no element is removed and `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::from_buf([1, 2, 3]);
+    ///
+    /// let mut cursor = set.lower_bound_mut(Bound::Included(&3));
+    /// assert_eq!(cursor.remove_current(), Some(3));
+    /// // Removing the last element moves the cursor to the ghost element.
+    /// assert_eq!(cursor.current(), None);
+    /// assert_eq!(cursor.remove_current(), None);
+    /// assert_eq!(cursor.peek_next(), Some(&1));
+    ///
+    /// assert_eq!(set.as_slice(), &[1, 2]);
+    /// ```
+    pub fn remove_current(&mut self) -> Option<S::Item> {
+        if self.idx < self.set.len() {
+            Some(self.set.storage.remove(self.idx))
+        } else {
+            None
+        }
+    }
+
+    /// Removes the current element from the set and returns it. The cursor is then moved to
+    /// point to the previous element.
+    ///
+    /// If the cursor is pointing to the ghost element, then no element is removed and `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::from_buf([1, 2, 3]);
+    ///
+    /// let mut cursor = set.lower_bound_mut(Bound::Included(&2));
+    /// assert_eq!(cursor.remove_current_and_move_back(), Some(2));
+    /// assert_eq!(cursor.current(), Some(&1));
+    ///
+    /// // Removing the first element moves the cursor back to the ghost element.
+    /// assert_eq!(cursor.remove_current_and_move_back(), Some(1));
+    /// assert_eq!(cursor.current(), None);
+    /// assert_eq!(cursor.remove_current_and_move_back(), None);
+    /// assert_eq!(cursor.peek_prev(), Some(&3));
+    ///
+    /// assert_eq!(set.as_slice(), &[3]);
+    /// ```
+    pub fn remove_current_and_move_back(&mut self) -> Option<S::Item> {
+        let element = self.remove_current()?;
+        self.idx = prev(self.idx, self.set.len());
+        Some(element)
+    }
+}
+
+impl<'a, S> CursorMut<'a, S>
+where
+    S: SortedStorage,
+    S::Item: Ord,
+{
+    /// Inserts a new element into the set immediately before the current one. The cursor is not
+    /// moved.
+    ///
+    /// If the cursor is pointing to the ghost element, the new element is inserted at the end of
+    /// the set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error containing the element if it is not strictly greater than the previous
+    /// element and strictly less than the current element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::from_buf([1, 3, 5]);
+    ///
+    /// let mut cursor = set.lower_bound_mut(Bound::Included(&3));
+    /// assert_eq!(cursor.insert_before(2), Ok(()));
+    /// assert_eq!(cursor.insert_before(4).unwrap_err().into_element(), 4);
+    /// assert_eq!(cursor.current(), Some(&3));
+    ///
+    /// assert_eq!(set.as_slice(), &[1, 2, 3, 5]);
+    ///
+    /// // At the ghost element, elements are appended to the end of the set.
+    /// let mut cursor = set.lower_bound_mut(Bound::Excluded(&5));
+    /// assert_eq!(cursor.current(), None);
+    /// assert_eq!(cursor.insert_before(4).unwrap_err().into_element(), 4);
+    /// assert_eq!(cursor.insert_before(6), Ok(()));
+    /// assert_eq!(cursor.current(), None);
+    ///
+    /// assert_eq!(set.as_slice(), &[1, 2, 3, 5, 6]);
+    /// ```
+    pub fn insert_before(
+        &mut self,
+        element: S::Item,
+    ) -> Result<(), UnorderedElementError<S::Item>> {
+        let prev = self
+            .idx
+            .checked_sub(1)
+            .and_then(|idx| self.set.as_slice().get(idx));
+        let next = self.set.as_slice().get(self.idx);
+        if !is_between(prev, &element, next) {
+            return Err(UnorderedElementError { element });
+        }
+
+        self.set.storage.insert(self.idx, element);
+        self.idx += 1;
+        Ok(())
+    }
+
+    /// Inserts a new element into the set immediately after the current one. The cursor is not
+    /// moved.
+    ///
+    /// If the cursor is pointing to the ghost element, the new element is inserted at the start
+    /// of the set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error containing the element if it is not strictly greater than the current
+    /// element and strictly less than the next element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::from_buf([2, 4]);
+    ///
+    /// let mut cursor = set.lower_bound_mut(Bound::Included(&2));
+    /// assert_eq!(cursor.insert_after(3), Ok(()));
+    /// assert_eq!(cursor.insert_after(5).unwrap_err().into_element(), 5);
+    /// assert_eq!(cursor.current(), Some(&2));
+    ///
+    /// // At the ghost element, elements are prepended to the start of the set.
+    /// let mut cursor = set.upper_bound_mut(Bound::Excluded(&2));
+    /// assert_eq!(cursor.current(), None);
+    /// assert_eq!(cursor.insert_after(2).unwrap_err().into_element(), 2);
+    /// assert_eq!(cursor.insert_after(1), Ok(()));
+    /// assert_eq!(cursor.current(), None);
+    /// assert_eq!(cursor.peek_next(), Some(&1));
+    ///
+    /// assert_eq!(set.as_slice(), &[1, 2, 3, 4]);
+    /// ```
+    pub fn insert_after(&mut self, element: S::Item) -> Result<(), UnorderedElementError<S::Item>> {
+        let len = self.set.len();
+        let idx = if self.idx == len { 0 } else { self.idx + 1 };
+        let prev = if self.idx == len {
+            None
+        } else {
+            self.set.as_slice().get(self.idx)
+        };
+        let next = self.set.as_slice().get(idx);
+        if !is_between(prev, &element, next) {
+            return Err(UnorderedElementError { element });
+        }
+
+        self.set.storage.insert(idx, element);
+        if self.idx == len {
+            self.idx += 1;
+        }
+        Ok(())
+    }
+}
+
+impl<T> UnorderedElementError<T> {
+    /// Take ownership of the element which could not be inserted.
+    pub fn into_element(self) -> T {
+        self.element
+    }
+}
+
+fn index(idx: usize, len: usize) -> Option<usize> {
+    if idx < len {
+        Some(idx)
+    } else {
+        None
+    }
+}
+
+fn next(idx: usize, len: usize) -> usize {
+    if idx == len {
+        0
+    } else {
+        idx + 1
+    }
+}
+
+fn prev(idx: usize, len: usize) -> usize {
+    if idx == 0 {
+        len
+    } else {
+        idx - 1
+    }
+}
+
+// `Option::is_none_or` would raise the minimum supported Rust version to 1.82.
+#[allow(clippy::unnecessary_map_or)]
+fn is_between<T: Ord>(prev: Option<&T>, element: &T, next: Option<&T>) -> bool {
+    prev.map_or(true, |prev| prev < element) && next.map_or(true, |next| element < next)
+}
+
+impl<S> Debug for Cursor<'_, S>
+where
+    S: SortedStorage,
+    S::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Cursor").field(&self.current()).finish()
+    }
+}
+
+impl<S> Debug for CursorMut<'_, S>
+where
+    S: SortedStorage,
+    S::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CursorMut").field(&self.current()).finish()
+    }
+}
+
+impl<T> Display for UnorderedElementError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("element is not strictly between its neighbours")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug> Error for UnorderedElementError<T> {}
@@ -1,28 +1,29 @@
-use std::fmt::{self, Debug};
+use alloc::borrow::ToOwned;
+use core::borrow::Borrow;
+use core::fmt::{self, Debug};
+use core::mem::replace;
 
-use smallvec::Array;
-
-use crate::{KeyValuePair, SmallOrdSet};
+use crate::{KeyValuePair, SortedSet, SortedStorage};
 
 /// A view into a single entry in a set, which may either be vacant or occupied.
 ///
-/// This `enum` is constructed from the [`entry`] method on [`SmallOrdSet`].
+/// This `enum` is constructed from the [`entry`] method on [`SortedSet`].
 ///
-/// [`SmallOrdSet`]: struct.SmallOrdSet.html
-/// [`entry`]: struct.SmallOrdSet.html#method.entry
-pub enum Entry<'a, A: Array, K> {
+/// [`SortedSet`]: struct.SortedSet.html
+/// [`entry`]: struct.SortedSet.html#method.entry
+pub enum Entry<'a, S: SortedStorage, K> {
     /// An occupied entry.
-    Occupied(OccupiedEntry<'a, A>),
+    Occupied(OccupiedEntry<'a, S>),
     /// A vacant entry.
-    Vacant(VacantEntry<'a, A, K>),
+    Vacant(VacantEntry<'a, S, K>),
 }
 
-/// A view into an occupied entry in a `SmallOrdSet`.
+/// A view into an occupied entry in a `SortedSet`.
 /// It is part of the [`Entry`] enum.
 ///
 /// [`Entry`]: enum.Entry.html
-pub struct OccupiedEntry<'a, A: Array> {
-    set: &'a mut SmallOrdSet<A>,
+pub struct OccupiedEntry<'a, S: SortedStorage> {
+    set: &'a mut SortedSet<S>,
     idx: usize,
 }
 
@@ -30,25 +31,49 @@ pub struct OccupiedEntry<'a, A: Array> {
 /// It is part of the [`Entry`] enum.
 ///
 /// [`Entry`]: enum.Entry.html
-pub struct VacantEntry<'a, A: Array, K> {
-    set: &'a mut SmallOrdSet<A>,
+pub struct VacantEntry<'a, S: SortedStorage, K> {
+    set: &'a mut SortedSet<S>,
     idx: usize,
     key: K,
 }
 
-impl<'a, A: Array, K> Entry<'a, A, K> {
-    pub(crate) fn occupied(set: &'a mut SmallOrdSet<A>, idx: usize) -> Self {
+/// A view into a single entry in a set, which may either be vacant or occupied, looked up by a
+/// borrowed key.
+///
+/// This `enum` is constructed from the [`entry_ref`] method on [`SortedSet`].
+///
+/// [`SortedSet`]: struct.SortedSet.html
+/// [`entry_ref`]: struct.SortedSet.html#method.entry_ref
+pub enum EntryRef<'a, 'b, S: SortedStorage, Q: ?Sized> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, S>),
+    /// A vacant entry.
+    Vacant(VacantEntryRef<'a, 'b, S, Q>),
+}
+
+/// A view into a vacant entry in a `SortedSet`, holding a borrowed key.
+/// It is part of the [`EntryRef`] enum.
+///
+/// [`EntryRef`]: enum.EntryRef.html
+pub struct VacantEntryRef<'a, 'b, S: SortedStorage, Q: ?Sized> {
+    set: &'a mut SortedSet<S>,
+    idx: usize,
+    key: &'b Q,
+}
+
+impl<'a, S: SortedStorage, K> Entry<'a, S, K> {
+    pub(crate) fn occupied(set: &'a mut SortedSet<S>, idx: usize) -> Self {
         Entry::Occupied(OccupiedEntry { set, idx })
     }
 
-    pub(crate) fn vacant(set: &'a mut SmallOrdSet<A>, idx: usize, key: K) -> Self {
+    pub(crate) fn vacant(set: &'a mut SortedSet<S>, idx: usize, key: K) -> Self {
         Entry::Vacant(VacantEntry { set, idx, key })
     }
 }
 
-impl<'a, A, K, V> Entry<'a, A, K>
+impl<'a, S, K, V> Entry<'a, S, K>
 where
-    A: Array<Item = KeyValuePair<K, V>>,
+    S: SortedStorage<Item = KeyValuePair<K, V>>,
     K: Ord + 'a,
     V: 'a,
 {
@@ -70,6 +95,38 @@ where
         }
     }
 
+    /// Ensures a value is in the entry by inserting, if empty, the result of the default function
+    /// called with a reference to the entry's key. Returns a mutable reference to the value in the
+    /// entry.
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty, and returns a
+    /// mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{SmallOrdSet, KeyValuePair};
+    ///
+    /// let mut map = SmallOrdSet::<[KeyValuePair<&str, Option<u32>>; 4]>::new();
+    ///
+    /// assert_eq!(map.entry("poneyland").or_default(), &mut None);
+    /// ```
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
     /// Returns a reference to this entry's key.
     pub fn key(&self) -> &K {
         match self {
@@ -94,24 +151,57 @@ where
     }
 }
 
-impl<'a, A> OccupiedEntry<'a, A>
+impl<'a, S> OccupiedEntry<'a, S>
 where
-    A: Array,
+    S: SortedStorage,
 {
     /// Gets a reference to the the entry.
-    pub fn get_entry(&self) -> &A::Item {
-        &self.set.vec[self.idx]
+    pub fn get_entry(&self) -> &S::Item {
+        &self.set.as_slice()[self.idx]
+    }
+
+    /// Converts the entry into a reference to the element in the set, with a lifetime bound to the
+    /// set itself.
+    pub fn into_entry(self) -> &'a S::Item {
+        &self.set.as_slice()[self.idx]
     }
 
     /// Take the ownership of the element from the set.
-    pub fn remove_entry(self) -> A::Item {
-        self.set.vec.remove(self.idx)
+    pub fn remove_entry(self) -> S::Item {
+        self.set.storage.remove(self.idx)
+    }
+}
+
+impl<'a, S> Entry<'a, S, S::Item>
+where
+    S: SortedStorage,
+    S::Item: Ord,
+{
+    /// Ensures the key is in the set by inserting it if the entry is empty, and returns a
+    /// reference to the element in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::<[String; 4]>::new();
+    ///
+    /// assert_eq!(set.entry("hello".to_owned()).or_insert_key(), "hello");
+    /// assert_eq!(set.entry("hello".to_owned()).or_insert_key(), "hello");
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn or_insert_key(self) -> &'a S::Item {
+        match self {
+            Entry::Occupied(entry) => entry.into_entry(),
+            Entry::Vacant(entry) => entry.insert_key(),
+        }
     }
 }
 
-impl<'a, A, K, V> OccupiedEntry<'a, A>
+impl<'a, S, K, V> OccupiedEntry<'a, S>
 where
-    A: Array<Item = KeyValuePair<K, V>>,
+    S: SortedStorage<Item = KeyValuePair<K, V>>,
     K: Ord + 'a,
     V: 'a,
 {
@@ -132,7 +222,7 @@ where
     ///
     /// [`into_mut`]: #method.into_mut
     pub fn get_mut(&mut self) -> &mut V {
-        &mut self.set.vec[self.idx].value
+        &mut self.set.storage.as_mut_slice()[self.idx].value
     }
 
     /// Converts the OccupiedEntry into a mutable reference to the value in the entry
@@ -142,11 +232,44 @@ where
     ///
     /// [`get_mut`]: #method.get_mut
     pub fn into_mut(self) -> &'a mut V {
-        &mut self.set.vec[self.idx].value
+        &mut self.set.storage.as_mut_slice()[self.idx].value
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{SmallOrdSet, KeyValuePair, Entry};
+    ///
+    /// let mut map = SmallOrdSet::<[KeyValuePair<&str, u32>; 4]>::new();
+    /// map.insert_value("poneyland", 12);
+    ///
+    /// if let Entry::Occupied(mut o) = map.entry("poneyland") {
+    ///     assert_eq!(o.insert(15), 12);
+    /// }
+    /// assert_eq!(map.get_value(&"poneyland"), Some(&15));
+    /// ```
+    pub fn insert(&mut self, value: V) -> V {
+        replace(self.get_mut(), value)
+    }
+
+    /// Takes the value of the entry out of the map, and returns it.
+    pub fn remove(self) -> V {
+        self.remove_entry().value
+    }
+
+    /// Replaces the key in the entry with the given one, and returns the entry's old key. This
+    /// is useful to swap in a different, but equal, instance of the key.
+    ///
+    /// The new key must compare equal to the existing one.
+    pub fn replace_key(&mut self, key: K) -> K {
+        debug_assert!(self.key() == &key);
+        replace(&mut self.set.storage.as_mut_slice()[self.idx].key, key)
     }
 }
 
-impl<'a, A: Array, K> VacantEntry<'a, A, K> {
+impl<'a, S: SortedStorage, K> VacantEntry<'a, S, K> {
     /// Gets a reference to the key that would be used when inserting a value through the VacantEntry.
     pub fn key(&self) -> &K {
         &self.key
@@ -160,19 +283,33 @@ impl<'a, A: Array, K> VacantEntry<'a, A, K> {
     /// Insert an element using the given constructor.
     ///
     /// The ordering of the computed element must match that of the key.
-    pub fn insert_with<F>(self, f: F) -> &'a mut A::Item
+    pub fn insert_with<F>(self, f: F) -> &'a mut S::Item
     where
-        F: FnOnce(K) -> A::Item,
+        F: FnOnce(K) -> S::Item,
     {
         let element = f(self.key);
-        self.set.vec.insert(self.idx, element);
-        &mut self.set.vec[self.idx]
+        self.set.storage.insert(self.idx, element);
+        &mut self.set.storage.as_mut_slice()[self.idx]
     }
 }
 
-impl<'a, A, K, V> VacantEntry<'a, A, K>
+impl<'a, S> VacantEntry<'a, S, S::Item>
 where
-    A: Array<Item = KeyValuePair<K, V>>,
+    S: SortedStorage,
+    S::Item: Ord,
+{
+    /// Inserts the VacantEntry's key into the set, and returns a reference to it.
+    ///
+    /// This is the equivalent of [`insert`](#method.insert) for sets whose element type is the
+    /// key itself.
+    pub fn insert_key(self) -> &'a S::Item {
+        self.insert_with(|key| key)
+    }
+}
+
+impl<'a, S, K, V> VacantEntry<'a, S, K>
+where
+    S: SortedStorage<Item = KeyValuePair<K, V>>,
     K: Ord + 'a,
     V: 'a,
 {
@@ -180,12 +317,142 @@ where
     pub fn insert(self, value: V) -> &'a mut V {
         &mut self.insert_with(|key| KeyValuePair { key, value }).value
     }
+
+    /// Sets the value of the entry with the VacantEntry's key, and returns an `OccupiedEntry`
+    /// pointing to it.
+    pub fn insert_entry(self, value: V) -> OccupiedEntry<'a, S> {
+        let VacantEntry { set, idx, key } = self;
+        set.storage.insert(idx, KeyValuePair { key, value });
+        OccupiedEntry { set, idx }
+    }
+}
+
+impl<'a, 'b, S: SortedStorage, Q: ?Sized> EntryRef<'a, 'b, S, Q> {
+    pub(crate) fn occupied(set: &'a mut SortedSet<S>, idx: usize) -> Self {
+        EntryRef::Occupied(OccupiedEntry { set, idx })
+    }
+
+    pub(crate) fn vacant(set: &'a mut SortedSet<S>, idx: usize, key: &'b Q) -> Self {
+        EntryRef::Vacant(VacantEntryRef { set, idx, key })
+    }
+}
+
+impl<'a, 'b, S, K, V, Q> EntryRef<'a, 'b, S, Q>
+where
+    S: SortedStorage<Item = KeyValuePair<K, V>>,
+    K: Ord + Borrow<Q> + 'a,
+    V: 'a,
+    Q: Ord + ToOwned<Owned = K> + ?Sized,
+{
+    /// Ensures a value is in the entry by inserting the default if empty, and returns
+    /// a mutable reference to the value in the entry.
+    ///
+    /// The key is only converted to its owned form if a new element is inserted.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            EntryRef::Occupied(entry) => entry.into_mut(),
+            EntryRef::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            EntryRef::Occupied(entry) => entry.into_mut(),
+            EntryRef::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting, if empty, the result of the default function
+    /// called with a reference to the entry's key. Returns a mutable reference to the value in the
+    /// entry.
+    pub fn or_insert_with_key<F: FnOnce(&Q) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            EntryRef::Occupied(entry) => entry.into_mut(),
+            EntryRef::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty, and returns a
+    /// mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &Q {
+        match self {
+            EntryRef::Occupied(entry) => entry.key().borrow(),
+            EntryRef::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            EntryRef::Occupied(mut entry) => {
+                f(entry.get_mut());
+                EntryRef::Occupied(entry)
+            }
+            EntryRef::Vacant(entry) => EntryRef::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, 'b, S: SortedStorage, Q: ?Sized> VacantEntryRef<'a, 'b, S, Q> {
+    /// Gets a reference to the key that would be used when inserting a value through the
+    /// VacantEntryRef.
+    pub fn key(&self) -> &'b Q {
+        self.key
+    }
+
+    /// Insert an element using the given constructor.
+    ///
+    /// The ordering of the computed element must match that of the key.
+    pub fn insert_with<F>(self, f: F) -> &'a mut S::Item
+    where
+        F: FnOnce(&'b Q) -> S::Item,
+    {
+        let element = f(self.key);
+        self.set.storage.insert(self.idx, element);
+        &mut self.set.storage.as_mut_slice()[self.idx]
+    }
 }
 
-impl<A, K> Debug for Entry<'_, A, K>
+impl<'a, 'b, S, K, V, Q> VacantEntryRef<'a, 'b, S, Q>
 where
-    A: Array,
-    A::Item: Debug,
+    S: SortedStorage<Item = KeyValuePair<K, V>>,
+    K: Ord + Borrow<Q> + 'a,
+    V: 'a,
+    Q: Ord + ToOwned<Owned = K> + ?Sized,
+{
+    /// Sets the value of the entry with the owned form of the VacantEntryRef's key, and returns a
+    /// mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        &mut self
+            .insert_with(|key| KeyValuePair {
+                key: key.to_owned(),
+                value,
+            })
+            .value
+    }
+}
+
+impl<S, K> Debug for Entry<'_, S, K>
+where
+    S: SortedStorage,
+    S::Item: Debug,
     K: Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -196,10 +463,10 @@ where
     }
 }
 
-impl<A> Debug for OccupiedEntry<'_, A>
+impl<S> Debug for OccupiedEntry<'_, S>
 where
-    A: Array,
-    A::Item: Debug,
+    S: SortedStorage,
+    S::Item: Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("OccupiedEntry")
@@ -208,12 +475,36 @@ where
     }
 }
 
-impl<A, K> Debug for VacantEntry<'_, A, K>
+impl<S, K> Debug for VacantEntry<'_, S, K>
 where
-    A: Array,
+    S: SortedStorage,
     K: Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("VacantEntry").finish()
     }
 }
+
+impl<S, Q> Debug for EntryRef<'_, '_, S, Q>
+where
+    S: SortedStorage,
+    S::Item: Debug,
+    Q: Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            EntryRef::Vacant(ref v) => f.debug_tuple("EntryRef").field(v).finish(),
+            EntryRef::Occupied(ref o) => f.debug_tuple("EntryRef").field(o).finish(),
+        }
+    }
+}
+
+impl<S, Q> Debug for VacantEntryRef<'_, '_, S, Q>
+where
+    S: SortedStorage,
+    Q: Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntryRef").field(&self.key).finish()
+    }
+}
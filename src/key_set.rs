@@ -0,0 +1,493 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{Deref, DerefMut, RangeBounds};
+use core::slice;
+
+use smallvec::{self, Array, SmallVec};
+
+use crate::{Comparable, Entry, KeyValuePair, SmallOrdSet};
+
+/// A type which is ordered and looked up by a key contained within it.
+pub trait Keyed {
+    /// The type of the key.
+    type Key: Ord;
+
+    /// Gets a reference to the key of this value.
+    fn key(&self) -> &Self::Key;
+}
+
+/// A set represented by a `SmallVec`, sorted by the [`Keyed::key`](trait.Keyed.html#tymethod.key)
+/// of its elements.
+///
+/// Elements are compared only by their key, so the set can hold at most one element for each key.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{Keyed, SmallOrdKeySet};
+///
+/// struct User {
+///     id: u32,
+///     name: &'static str,
+/// }
+///
+/// impl Keyed for User {
+///     type Key = u32;
+///
+///     fn key(&self) -> &u32 {
+///         &self.id
+///     }
+/// }
+///
+/// let mut users = SmallOrdKeySet::<[User; 4]>::new();
+/// users.insert(User { id: 2, name: "bob" });
+/// users.insert(User { id: 1, name: "alice" });
+///
+/// assert_eq!(users.get(&2).map(|user| user.name), Some("bob"));
+///
+/// users.get_mut(&1).unwrap().name = "carol";
+/// assert_eq!(users.first().map(|user| user.name), Some("carol"));
+/// ```
+pub struct SmallOrdKeySet<A: Array> {
+    set: SmallOrdSet<A>,
+}
+
+/// A mutable reference to an element of a [`SmallOrdKeySet`](struct.SmallOrdKeySet.html).
+///
+/// This struct is created by the [`get_mut`](struct.SmallOrdKeySet.html#method.get_mut) method.
+/// If the key of the element is changed, the element is moved to its new position in the set when
+/// this guard is dropped, replacing any other element with the same key.
+pub struct KeyedMut<'a, A>
+where
+    A: Array,
+    A::Item: Keyed,
+{
+    set: &'a mut SmallOrdSet<A>,
+    idx: usize,
+}
+
+impl<A: Array> SmallOrdKeySet<A> {
+    /// Make a new, empty, `SmallOrdKeySet`.
+    pub fn new() -> Self {
+        SmallOrdKeySet::default()
+    }
+
+    /// Construct a new [`SmallOrdKeySet`](struct.SmallOrdKeySet.html) from a `SmallVec` sorted by
+    /// key. `vec` must be sorted and may not contain duplicate keys.
+    ///
+    /// # Safety
+    ///
+    /// Failure to uphold the restrictions on the `vec` parameter will not cause memory unsafety,
+    /// however the result of any operations on the resulting `SmallOrdKeySet` is unspecified.
+    pub fn from_vec_unchecked(vec: SmallVec<A>) -> Self {
+        SmallOrdKeySet {
+            set: SmallOrdSet::from_vec_unchecked(vec),
+        }
+    }
+
+    /// Get a slice containing the whole set in sorted order.
+    pub fn as_slice(&self) -> &[A::Item] {
+        self.set.as_slice()
+    }
+
+    /// The number of elements the set can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.set.capacity()
+    }
+
+    /// Remove all elements from the set.
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    /// Creates a draining iterator that removes the specified range in the set
+    /// and yields the removed items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the set.
+    pub fn drain<R>(&mut self, range: R) -> smallvec::Drain<'_, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.set.drain(range)
+    }
+
+    /// The maximum number of elements this set can hold inline
+    pub fn inline_size(&self) -> usize {
+        self.set.inline_size()
+    }
+
+    /// Convert the set into the inner `SmallVec`.
+    pub fn into_vec(self) -> SmallVec<A> {
+        self.set.into_vec()
+    }
+
+    /// The number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Reserve capacity for `additional` more elements to be inserted.
+    ///
+    /// May reserve more space to avoid frequent reallocations.
+    pub fn reserve(&mut self, additional: usize) {
+        self.set.reserve(additional)
+    }
+
+    /// Construct an iterator over the set, in ascending order of key.
+    pub fn iter(&self) -> slice::Iter<'_, A::Item> {
+        self.set.iter()
+    }
+
+    /// Returns a reference to the element with the smallest key, if any.
+    pub fn first(&self) -> Option<&A::Item> {
+        self.set.first()
+    }
+
+    /// Returns a reference to the element with the largest key, if any.
+    pub fn last(&self) -> Option<&A::Item> {
+        self.set.last()
+    }
+}
+
+impl<A> SmallOrdKeySet<A>
+where
+    A: Array,
+    A::Item: Keyed,
+{
+    /// Construct a new [`SmallOrdKeySet`](struct.SmallOrdKeySet.html) from a `SmallVec`. The
+    /// vector will be sorted by key and elements with duplicate keys removed.
+    pub fn from_vec(vec: SmallVec<A>) -> Self {
+        let mut set = SmallOrdKeySet::from_vec_unchecked(vec);
+        set.sort_and_dedup();
+        set
+    }
+
+    /// Constructs a new [`SmallOrdKeySet`](struct.SmallOrdKeySet.html) on the stack from an `A`
+    /// without copying elements.
+    pub fn from_buf(buf: A) -> Self {
+        SmallOrdKeySet::from_vec(buf.into())
+    }
+
+    /// Adds an element to the set.
+    ///
+    /// If the set did not have an element with the same key present, `true` is returned.
+    ///
+    /// If the set did have an element with the same key present, `false` is returned, and the
+    /// entry is not updated.
+    pub fn insert(&mut self, element: A::Item) -> bool {
+        self.set.insert_by(element, cmp_keys)
+    }
+
+    /// Adds a element to the set, replacing the existing element, if any, that has the same key
+    /// as the given one. Returns the replaced element.
+    pub fn replace(&mut self, element: A::Item) -> Option<A::Item> {
+        self.set.replace_by(element, cmp_keys)
+    }
+
+    /// Removes and returns the element in the set, if any, with the given key.
+    ///
+    /// The key may be any borrowed form of the set's key type, or any other type implementing
+    /// [`Comparable`](trait.Comparable.html), but the ordering on that type *must* match the
+    /// ordering on the key type.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<A::Item>
+    where
+        Q: Comparable<<A::Item as Keyed>::Key> + ?Sized,
+    {
+        self.set
+            .remove_by(|probe| key.compare(probe.key()).reverse())
+    }
+
+    /// Returns `true` if the set contains an element with the given key.
+    ///
+    /// The key may be any borrowed form of the set's key type, or any other type implementing
+    /// [`Comparable`](trait.Comparable.html), but the ordering on that type *must* match the
+    /// ordering on the key type.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: Comparable<<A::Item as Keyed>::Key> + ?Sized,
+    {
+        self.find(key).is_ok()
+    }
+
+    /// Returns a reference to the element in the set, if any, with the given key.
+    ///
+    /// The key may be any borrowed form of the set's key type, or any other type implementing
+    /// [`Comparable`](trait.Comparable.html), but the ordering on that type *must* match the
+    /// ordering on the key type.
+    pub fn get<Q>(&self, key: &Q) -> Option<&A::Item>
+    where
+        Q: Comparable<<A::Item as Keyed>::Key> + ?Sized,
+    {
+        match self.find(key) {
+            Ok(idx) => Some(&self.set.storage[idx]),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the element in the set, if any, with the given key.
+    ///
+    /// The whole element may be modified through the returned [`KeyedMut`](struct.KeyedMut.html).
+    /// If its key is changed, the element is moved to its new position when the guard is dropped,
+    /// replacing any other element with the same key.
+    ///
+    /// The key may be any borrowed form of the set's key type, or any other type implementing
+    /// [`Comparable`](trait.Comparable.html), but the ordering on that type *must* match the
+    /// ordering on the key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{KeyValuePair, SmallOrdKeySet};
+    ///
+    /// let mut set = SmallOrdKeySet::<[KeyValuePair<u32, &str>; 4]>::new();
+    /// set.insert(KeyValuePair { key: 3, value: "three" });
+    ///
+    /// set.get_mut(&3).unwrap().value = "drei";
+    /// assert_eq!(set.get(&3).map(|kvp| kvp.value), Some("drei"));
+    ///
+    /// set.insert(KeyValuePair { key: 5, value: "five" });
+    /// set.get_mut(&3).unwrap().key = 9;
+    /// assert_eq!(set.keys().collect::<Vec<_>>(), [&5, &9]);
+    ///
+    /// set.get_mut(&9).unwrap().key = 5;
+    /// assert_eq!(set.as_slice(), &[KeyValuePair { key: 5, value: "drei" }]);
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<KeyedMut<'_, A>>
+    where
+        Q: Comparable<<A::Item as Keyed>::Key> + ?Sized,
+    {
+        match self.find(key) {
+            Ok(idx) => Some(KeyedMut {
+                set: &mut self.set,
+                idx,
+            }),
+            Err(_) => None,
+        }
+    }
+
+    /// Gets the given key's corresponding entry in the set for in-place manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{Entry, KeyValuePair, SmallOrdKeySet};
+    ///
+    /// let mut set = SmallOrdKeySet::<[KeyValuePair<u32, &str>; 4]>::new();
+    ///
+    /// if let Entry::Vacant(entry) = set.entry(1) {
+    ///     entry.insert_with(|key| KeyValuePair { key, value: "one" });
+    /// }
+    ///
+    /// assert_eq!(set.get(&1).map(|kvp| kvp.value), Some("one"));
+    /// ```
+    pub fn entry(
+        &mut self,
+        key: <A::Item as Keyed>::Key,
+    ) -> Entry<'_, SmallVec<A>, <A::Item as Keyed>::Key> {
+        match self.find(&key) {
+            Ok(idx) => Entry::occupied(&mut self.set, idx),
+            Err(idx) => Entry::vacant(&mut self.set, idx, key),
+        }
+    }
+
+    /// Get an iterator over all keys in the set.
+    pub fn keys(&self) -> impl Iterator<Item = &<A::Item as Keyed>::Key> + Clone {
+        self.iter().map(Keyed::key)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        Q: Comparable<<A::Item as Keyed>::Key> + ?Sized,
+    {
+        self.set.find_by(|probe| key.compare(probe.key()).reverse())
+    }
+
+    fn sort_and_dedup(&mut self) {
+        self.set.sort_and_dedup_by(cmp_keys)
+    }
+}
+
+impl<K: Ord, V> Keyed for KeyValuePair<K, V> {
+    type Key = K;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+fn cmp_keys<T: Keyed>(a: &T, b: &T) -> Ordering {
+    Ord::cmp(a.key(), b.key())
+}
+
+impl<A: Array> AsRef<[A::Item]> for SmallOrdKeySet<A> {
+    fn as_ref(&self) -> &[A::Item] {
+        self.as_slice()
+    }
+}
+
+impl<A> Clone for SmallOrdKeySet<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallOrdKeySet {
+            set: self.set.clone(),
+        }
+    }
+}
+
+impl<A> Debug for SmallOrdKeySet<A>
+where
+    A: Array,
+    A::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<A: Array> Default for SmallOrdKeySet<A> {
+    fn default() -> Self {
+        SmallOrdKeySet::from_vec_unchecked(SmallVec::new())
+    }
+}
+
+impl<A: Array> Deref for SmallOrdKeySet<A> {
+    type Target = [A::Item];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<A> Eq for SmallOrdKeySet<A>
+where
+    A: Array,
+    A::Item: Eq,
+{
+}
+
+impl<A> Extend<A::Item> for SmallOrdKeySet<A>
+where
+    A: Array,
+    A::Item: Keyed,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = A::Item>,
+    {
+        self.set.storage.extend(iter);
+        self.sort_and_dedup();
+    }
+}
+
+impl<A> FromIterator<A::Item> for SmallOrdKeySet<A>
+where
+    A: Array,
+    A::Item: Keyed,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = A::Item>,
+    {
+        SmallOrdKeySet::from_vec(FromIterator::from_iter(iter))
+    }
+}
+
+impl<A> Hash for SmallOrdKeySet<A>
+where
+    A: Array,
+    A::Item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.set.hash(state)
+    }
+}
+
+impl<A: Array> IntoIterator for SmallOrdKeySet<A> {
+    type IntoIter = smallvec::IntoIter<A>;
+    type Item = A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.into_iter()
+    }
+}
+
+impl<'a, A: Array> IntoIterator for &'a SmallOrdKeySet<A> {
+    type IntoIter = slice::Iter<'a, A::Item>;
+    type Item = &'a A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<A> PartialEq for SmallOrdKeySet<A>
+where
+    A: Array,
+    A::Item: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&self.set, &other.set)
+    }
+}
+
+impl<A> Debug for KeyedMut<'_, A>
+where
+    A: Array,
+    A::Item: Keyed + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<A> Deref for KeyedMut<'_, A>
+where
+    A: Array,
+    A::Item: Keyed,
+{
+    type Target = A::Item;
+
+    fn deref(&self) -> &Self::Target {
+        &self.set.storage[self.idx]
+    }
+}
+
+impl<A> DerefMut for KeyedMut<'_, A>
+where
+    A: Array,
+    A::Item: Keyed,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.set.storage[self.idx]
+    }
+}
+
+impl<A> Drop for KeyedMut<'_, A>
+where
+    A: Array,
+    A::Item: Keyed,
+{
+    fn drop(&mut self) {
+        let slice = self.set.as_slice();
+        let key = slice[self.idx].key();
+        let after_prev = self.idx == 0 || slice[self.idx - 1].key() < key;
+        let before_next = self.idx + 1 == slice.len() || key < slice[self.idx + 1].key();
+        if !(after_prev && before_next) {
+            let element = self.set.storage.remove(self.idx);
+            self.set.replace_by(element, cmp_keys);
+        }
+    }
+}
@@ -1,182 +1,377 @@
-//! This crate provides the [`SmallOrdSet`](struct.SmallOrdSet.html) type, a set data-structure
-//! represented by a sorted `SmallVec`.
+//! This crate provides the [`SmallOrdSet`](type.SmallOrdSet.html) type, a set data-structure
+//! represented by a sorted `SmallVec`. The same set and map logic is available for other
+//! containers through [`SortedSet`](struct.SortedSet.html) and the
+//! [`SortedStorage`](trait.SortedStorage.html) trait.
+//!
+//! The crate supports `no_std` environments with `alloc` by disabling the default `std` feature.
+//! Error types implement `std::error::Error` only when the `std` feature is enabled.
+//!
+//! With the `serde` feature, a `SmallOrdSet` is serialized as a sequence. This includes sets of
+//! [`KeyValuePair`](struct.KeyValuePair.html)s, which become sequences of `[key, value]` tuples;
+//! use [`SmallOrdMap`](struct.SmallOrdMap.html) or the [`serde_helpers`](serde_helpers/index.html)
+//! module to serialize them as maps instead.
 
-#![doc(html_root_url = "https://docs.rs/small-ord-set/0.1.3")]
+#![doc(html_root_url = "https://docs.rs/small-ord-set/0.2.0")]
 #![deny(
     missing_debug_implementations,
     missing_copy_implementations,
     missing_docs
 )]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+#[cfg(feature = "rkyv")]
+mod archive;
+mod array_set;
+mod bi_map;
+mod comparable;
+mod comparator;
+mod const_set;
+mod cursor;
 mod entry;
+mod key_set;
+mod macros;
 mod map;
+mod multi_map;
+mod multi_set;
+mod range_map;
+mod range_set;
+#[cfg(feature = "serde")]
+pub mod serde_helpers;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod set_by;
+mod shared_set;
+#[cfg(feature = "sparse")]
+mod sparse;
+mod storage;
+mod top_k;
 
+#[cfg(feature = "rkyv")]
+pub use self::archive::*;
+pub use self::array_set::*;
+pub use self::bi_map::*;
+pub use self::comparable::*;
+pub use self::comparator::*;
+pub use self::cursor::*;
 pub use self::entry::*;
+pub use self::key_set::*;
+#[doc(hidden)]
+pub use self::macros::__private;
 pub use self::map::*;
+pub use self::multi_map::*;
+pub use self::multi_set::*;
+pub use self::range_map::*;
+pub use self::range_set::*;
+pub use self::set_by::*;
+pub use self::shared_set::*;
+#[cfg(feature = "sparse")]
+pub use self::sparse::*;
+pub use self::storage::SortedStorage;
+pub use self::top_k::*;
+
+pub use smallvec::CollectionAllocErr;
 
-use std::borrow::Borrow;
-use std::cmp::Ordering;
-use std::fmt::{self, Debug};
-use std::hash::{Hash, Hasher};
-use std::iter::FromIterator;
-use std::mem::replace;
-use std::ops::{Deref, Index, RangeBounds};
-use std::slice::{self, SliceIndex};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::{Bound, Deref, Index, RangeBounds};
+use core::slice::{self, SliceIndex};
 
 use smallvec::{self, Array, SmallVec};
 
-/// A set represented by a sorted `SmallVec`.
-pub struct SmallOrdSet<A: Array> {
-    vec: SmallVec<A>,
+/// A set represented by a sorted container of any type implementing
+/// [`SortedStorage`](trait.SortedStorage.html).
+///
+/// [`SmallOrdSet`](type.SmallOrdSet.html) is the `SortedSet` backed by a `SmallVec`. Other
+/// containers, such as `Vec` or a fixed-capacity vector, share the same ordered set and map logic.
+///
+/// Methods which add elements, including those on [`Entry`](enum.Entry.html) and
+/// [`CursorMut`](struct.CursorMut.html), panic if a bounded container is full. The `try_` methods
+/// may be used to handle a full container without panicking.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{KeyValuePair, SortedSet};
+///
+/// let mut set = SortedSet::<Vec<u32>>::new();
+///
+/// assert!(set.insert(3));
+/// assert!(set.insert(1));
+/// assert!(!set.insert(3));
+/// assert_eq!(set.as_slice(), &[1, 3]);
+/// assert_eq!(set.into_storage(), vec![1, 3]);
+///
+/// let mut words = SortedSet::<Vec<KeyValuePair<&str, u32>>>::new();
+/// for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+///     *words.entry(word).or_insert(0) += 1;
+/// }
+/// assert_eq!(words.get_value("the"), Some(&2));
+///
+/// let letters: SortedSet<Vec<char>> = "hello".chars().collect();
+/// assert_eq!(letters.into_iter().collect::<String>(), "ehlo");
+/// ```
+pub struct SortedSet<S> {
+    storage: S,
 }
 
-impl<A: Array> SmallOrdSet<A> {
-    /// Make a new, empty, `SmallOrdSet`.
-    pub fn new() -> Self {
-        SmallOrdSet::default()
-    }
+/// A set represented by a sorted `SmallVec`.
+///
+/// This is the [`SortedSet`](struct.SortedSet.html) backed by a `SmallVec<A>`, so all methods of
+/// `SortedSet` are available, along with methods specific to `SmallVec` such as
+/// [`inline_size`](struct.SortedSet.html#method.inline_size) and
+/// [`try_reserve`](struct.SortedSet.html#method.try_reserve).
+pub type SmallOrdSet<A> = SortedSet<SmallVec<A>>;
 
-    /// Get a slice containing the whole set in sorted order.
-    pub fn as_slice(&self) -> &[A::Item] {
-        self.vec.as_slice()
-    }
+/// A [`SmallOrdSet`](type.SmallOrdSet.html) storing up to `N` elements of type `T` inline.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::SmallOrdSetN;
+///
+/// let mut set = SmallOrdSetN::<u32, 4>::new();
+///
+/// set.insert(3);
+/// set.insert(1);
+/// assert_eq!(set.as_slice(), &[1, 3]);
+/// ```
+pub type SmallOrdSetN<T, const N: usize> = SmallOrdSet<[T; N]>;
 
-    /// The number of elements the set can hold without reallocating
-    pub fn capacity(&self) -> usize {
-        self.vec.capacity()
+impl<S: SortedStorage> SortedSet<S> {
+    /// Make a new, empty, set.
+    pub fn new() -> Self
+    where
+        S: Default,
+    {
+        SortedSet::default()
     }
 
-    /// Remove all elements from the set.
-    pub fn clear(&mut self) {
-        self.vec.clear();
+    /// Construct a new set from a container which must already be sorted in strictly ascending
+    /// order.
+    ///
+    /// It is a logic error to pass a container which is not sorted or contains duplicate
+    /// elements.
+    pub fn from_storage_unchecked(storage: S) -> Self {
+        SortedSet { storage }
     }
 
-    /// Creates a draining iterator that removes the specified range in the set
-    /// and yields the removed items.
-    ///
-    /// Note 1: The element range is removed even if the iterator is only
-    /// partially consumed or not consumed at all.
-    ///
-    /// Note 2: It is unspecified how many elements are removed from the set
-    /// if the `Drain` value is leaked.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the starting point is greater than the end point or if
-    /// the end point is greater than the length of the set.
-    pub fn drain<R>(&mut self, range: R) -> smallvec::Drain<A>
-    where
-        R: RangeBounds<usize>,
-    {
-        self.vec.drain(range)
+    /// Get a reference to the underlying container.
+    pub fn as_storage(&self) -> &S {
+        &self.storage
     }
 
-    /// Re-allocate to set the capacity to `max(new_cap, inline_size())`.
-    ///
-    /// Panics if `new_cap` is less than the set's length.
-    pub fn grow(&mut self, new_cap: usize) {
-        self.vec.grow(new_cap)
+    /// Convert into the underlying container.
+    pub fn into_storage(self) -> S {
+        self.storage
     }
 
-    /// The maximum number of elements this set can hold inline
-    pub fn inline_size(&self) -> usize {
-        self.vec.inline_size()
+    /// Get a slice containing the whole set in sorted order.
+    pub fn as_slice(&self) -> &[S::Item] {
+        self.storage.as_slice()
     }
 
-    /// Convert the set into the inner `SmallVec`.
-    pub fn into_vec(self) -> SmallVec<A> {
-        self.vec
+    /// Remove all elements from the set.
+    pub fn clear(&mut self) {
+        self.storage.truncate(0);
     }
 
     /// The number of elements in the set.
     pub fn len(&self) -> usize {
-        self.vec.len()
+        self.as_slice().len()
     }
 
-    /// Returns `true` if the vector is empty.
+    /// Returns `true` if the set is empty.
     pub fn is_empty(&self) -> bool {
-        self.vec.is_empty()
+        self.as_slice().is_empty()
     }
 
-    /// Reserve capacity for `additional` more elements to be inserted.
+    /// Construct an iterator over the set, in ascending order.
+    pub fn iter(&self) -> slice::Iter<'_, S::Item> {
+        self.as_slice().iter()
+    }
+
+    /// Binary searches the set with a comparator function.
     ///
-    /// May reserve more space to avoid frequent reallocations.
-    pub fn reserve(&mut self, additional: usize) {
-        self.vec.reserve(additional)
+    /// The comparator function should return the ordering of the given element relative to the
+    /// target, and must be consistent with the order of the set. If the target is found then
+    /// `Ok` is returned, containing its index. If it is not found then `Err` is returned,
+    /// containing the index where a matching element could be inserted while maintaining sorted
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let set = SmallOrdSet::from_buf([(1, 'a'), (2, 'b'), (4, 'c')]);
+    ///
+    /// assert_eq!(set.find_by(|&(t, _)| t.cmp(&2)), Ok(1));
+    /// assert_eq!(set.find_by(|&(t, _)| t.cmp(&3)), Err(2));
+    /// ```
+    pub fn find_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&S::Item) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
     }
 
-    /// Reserve the minimum capacity for `additional` more elements to be inserted.
+    /// Returns a reference to the element in the set, if any, for which the comparator function
+    /// returns `Ordering::Equal`.
     ///
-    /// Panics if the new capacity overflows `usize`.
-    pub fn reserve_exact(&mut self, additional: usize) {
-        self.vec.reserve_exact(additional)
+    /// The comparator function should return the ordering of the given element relative to the
+    /// target, and must be consistent with the order of the set.
+    pub fn get_by<F>(&self, f: F) -> Option<&S::Item>
+    where
+        F: FnMut(&S::Item) -> Ordering,
+    {
+        match self.find_by(f) {
+            Ok(idx) => Some(&self.as_slice()[idx]),
+            Err(_) => None,
+        }
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Returns the index of the first element for which the predicate returns `false`.
     ///
-    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
-    /// This method operates in place and preserves the order of the retained
-    /// elements.
-    pub fn retain<F>(&mut self, f: F)
+    /// The predicate must return `true` for all elements in some prefix of the set, and `false`
+    /// for all remaining elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// // Events ordered by timestamp, then sequence number.
+    /// let events = SmallOrdSet::from_buf([(10, 0), (10, 1), (20, 0), (30, 0)]);
+    ///
+    /// // Find the first event after time 10.
+    /// let idx = events.partition_point(|&(t, _)| t <= 10);
+    /// assert_eq!(events[idx], (20, 0));
+    /// ```
+    pub fn partition_point<P>(&self, pred: P) -> usize
     where
-        F: FnMut(&mut A::Item) -> bool,
+        P: FnMut(&S::Item) -> bool,
     {
-        self.vec.retain(f)
+        self.as_slice().partition_point(pred)
     }
 
-    /// Construct a new [`SmallOrdSet`](struct.SmallOrdSet.html) from a sorted `SmallVec`. `vec` must
-    /// be sorted and may not contain duplicate elements.
+    /// Returns the slice of elements between the bounds given by two comparator functions.
     ///
-    /// # Safety
+    /// An element is included if `lower` returns `Ordering::Equal` or `Ordering::Greater` for it,
+    /// and `upper` returns `Ordering::Less` or `Ordering::Equal` for it. Both comparator functions
+    /// should return the ordering of the given element relative to their bound, and must be
+    /// consistent with the order of the set.
     ///
-    /// Failure to uphold the restrictions on the `vec` parameter will not cause memory unsafety,
-    /// however the result of any operations on the resulting `SmallOrdSet` is unspecified.
-    pub fn from_vec_unchecked(vec: SmallVec<A>) -> Self {
-        SmallOrdSet { vec }
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let events = SmallOrdSet::from_buf([(10, 0), (10, 1), (20, 0), (30, 0)]);
+    ///
+    /// let range = events.range_by(|&(t, _)| t.cmp(&10), |&(t, _)| t.cmp(&20));
+    /// assert_eq!(range, &[(10, 0), (10, 1), (20, 0)]);
+    /// ```
+    pub fn range_by<L, U>(&self, mut lower: L, mut upper: U) -> &[S::Item]
+    where
+        L: FnMut(&S::Item) -> Ordering,
+        U: FnMut(&S::Item) -> Ordering,
+    {
+        let start = self.partition_point(|probe| lower(probe) == Ordering::Less);
+        let end = self.partition_point(|probe| upper(probe) != Ordering::Greater);
+        &self.as_slice()[start..end.max(start)]
     }
 
-    /// Construct an iterator over the set, in ascending order.
-    pub fn iter(&self) -> slice::Iter<A::Item> {
-        self.vec.iter()
+    /// Removes and returns the element in the set, if any, for which the comparator function
+    /// returns `Ordering::Equal`.
+    ///
+    /// The comparator function should return the ordering of the given element relative to the
+    /// target, and must be consistent with the order of the set.
+    pub fn remove_by<F>(&mut self, f: F) -> Option<S::Item>
+    where
+        F: FnMut(&S::Item) -> Ordering,
+    {
+        match self.find_by(f) {
+            Ok(idx) => Some(self.storage.remove(idx)),
+            Err(_) => None,
+        }
+    }
+
+    pub(crate) fn insert_by<F>(&mut self, element: S::Item, mut f: F) -> bool
+    where
+        F: FnMut(&S::Item, &S::Item) -> Ordering,
+    {
+        match self.find_by(|probe| f(probe, &element)) {
+            Ok(_) => false,
+            Err(idx) => {
+                self.storage.insert(idx, element);
+                true
+            }
+        }
+    }
+
+    pub(crate) fn replace_by<F>(&mut self, element: S::Item, mut f: F) -> Option<S::Item>
+    where
+        F: FnMut(&S::Item, &S::Item) -> Ordering,
+    {
+        match self.find_by(|probe| f(probe, &element)) {
+            Ok(idx) => Some(mem::replace(&mut self.storage.as_mut_slice()[idx], element)),
+            Err(idx) => {
+                self.storage.insert(idx, element);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn sort_and_dedup_by<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&S::Item, &S::Item) -> Ordering,
+    {
+        let slice = self.storage.as_mut_slice();
+        slice.sort_unstable_by(&mut f);
+
+        // Move the first of each run of equal elements to the front, then drop the rest.
+        let mut len = 0;
+        for idx in 0..slice.len() {
+            if len == 0 || f(&slice[idx], &slice[len - 1]) != Ordering::Equal {
+                slice.swap(idx, len);
+                len += 1;
+            }
+        }
+        self.storage.truncate(len);
     }
 
     /// Returns a reference to the first element in the set, if any. This element is always the minimum
     /// of all elements in the set.
-    pub fn first(&self) -> Option<&A::Item> {
-        self.vec.first()
+    pub fn first(&self) -> Option<&S::Item> {
+        self.as_slice().first()
     }
 
     /// Returns a reference to the first element in the set, if any. This element is always the maximum
     /// of all elements in the set.
-    pub fn last(&self) -> Option<&A::Item> {
-        self.vec.last()
+    pub fn last(&self) -> Option<&S::Item> {
+        self.as_slice().last()
     }
 }
 
-impl<A> SmallOrdSet<A>
+impl<S> SortedSet<S>
 where
-    A: Array,
-    A::Item: Ord,
+    S: SortedStorage,
+    S::Item: Ord,
 {
-    /// Moves all elements from `other` into `Self`, leaving other `empty`.
-    pub fn append(&mut self, other: &mut Self) {
-        self.extend(other.drain(..))
-    }
-
-    /// Construct a new [`SmallOrdSet`](struct.SmallOrdSet.html) from a `SmallVec`. The vector will be
-    /// sorted and duplicate elements removed.
-    pub fn from_vec(vec: SmallVec<A>) -> Self {
-        let mut set = SmallOrdSet::from_vec_unchecked(vec);
+    /// Construct a new set from a container. The container will be sorted and duplicate elements
+    /// removed.
+    pub fn from_storage(storage: S) -> Self {
+        let mut set = SortedSet::from_storage_unchecked(storage);
         set.sort_and_dedup();
         set
     }
 
-    /// Constructs a new [`SmallOrdSet`](struct.SmallOrdSet.html) on the stack from an `A` without
-    /// copying elements.
-    pub fn from_buf(buf: A) -> Self {
-        SmallOrdSet::from_vec(buf.into())
-    }
-
     /// Adds an element to the set.
     ///
     /// If the set did not have this element present, `true` is returned.
@@ -184,6 +379,10 @@ where
     /// If the set did have this element present, `false` is returned, and the
     /// entry is not updated.
     ///
+    /// # Panics
+    ///
+    /// Panics if the element is not present and the underlying container is full.
+    ///
     /// # Examples
     ///
     /// ```
@@ -195,19 +394,17 @@ where
     /// assert_eq!(set.insert(2), false);
     /// assert_eq!(set.len(), 1);
     /// ```
-    pub fn insert(&mut self, element: A::Item) -> bool {
-        match self.find(&element) {
-            Ok(_) => false,
-            Err(idx) => {
-                self.vec.insert(idx, element);
-                true
-            }
-        }
+    pub fn insert(&mut self, element: S::Item) -> bool {
+        self.insert_by(element, Ord::cmp)
     }
 
     /// Adds a element to the set, replacing the existing element, if any, that is equal to the given
     /// one. Returns the replaced element.
     ///
+    /// # Panics
+    ///
+    /// Panics if the element is not present and the underlying container is full.
+    ///
     /// # Examples
     ///
     /// ```
@@ -219,21 +416,57 @@ where
     /// assert_eq!(set.replace(2), Some(2));
     /// assert_eq!(set.len(), 1);
     /// ```
-    pub fn replace(&mut self, element: A::Item) -> Option<A::Item> {
+    pub fn replace(&mut self, element: S::Item) -> Option<S::Item> {
+        self.replace_by(element, Ord::cmp)
+    }
+
+    /// Adds an element to the set, returning an error instead of panicking or aborting if the
+    /// underlying container is full or space for the element cannot be allocated.
+    ///
+    /// On success, behaves like [`insert`](#method.insert). On failure, the set is unchanged. The
+    /// error type depends on the container: a `SmallOrdSet` returns a
+    /// [`CollectionAllocErr`](enum.CollectionAllocErr.html), while bounded containers return a
+    /// [`CapacityError`](struct.CapacityError.html) holding the element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::<[u32; 1]>::new();
+    ///
+    /// assert_eq!(set.try_insert(2).unwrap(), true);
+    /// assert_eq!(set.try_insert(1).unwrap(), true);
+    /// assert_eq!(set.try_insert(2).unwrap(), false);
+    /// assert_eq!(set.as_slice(), &[1, 2]);
+    /// ```
+    pub fn try_insert(&mut self, element: S::Item) -> Result<bool, S::Error> {
         match self.find(&element) {
-            Ok(idx) => Some(replace(&mut self.vec[idx], element)),
-            Err(idx) => {
-                self.vec.insert(idx, element);
-                None
-            }
+            Ok(_) => Ok(false),
+            Err(idx) => self.storage.try_insert(idx, element).map(|()| true),
+        }
+    }
+
+    /// Adds a element to the set, replacing the existing element, if any, that is equal to the
+    /// given one. Returns the replaced element, or an error if the element is not present and
+    /// cannot be inserted.
+    ///
+    /// On failure, the set is unchanged.
+    pub fn try_replace(&mut self, element: S::Item) -> Result<Option<S::Item>, S::Error> {
+        match self.find(&element) {
+            Ok(idx) => Ok(Some(mem::replace(
+                &mut self.storage.as_mut_slice()[idx],
+                element,
+            ))),
+            Err(idx) => self.storage.try_insert(idx, element).map(|()| None),
         }
     }
 
     /// Removes and returns the element in the set, if any, that is equal to the given one.
     ///
-    /// The element may be any borrowed form of the set's element type,
-    /// but the ordering on the borrowed form *must* match the
-    /// ordering on the element type.
+    /// The element may be any borrowed form of the set's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html), but the ordering on that type *must*
+    /// match the ordering on the element type.
     ///
     /// # Examples
     ///
@@ -246,22 +479,18 @@ where
     /// assert_eq!(set.remove(&2), Some(2));
     /// assert_eq!(set.remove(&2), None);
     /// ```
-    pub fn remove<Q>(&mut self, element: &Q) -> Option<A::Item>
+    pub fn remove<Q>(&mut self, element: &Q) -> Option<S::Item>
     where
-        A::Item: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<S::Item> + ?Sized,
     {
-        match self.find(element) {
-            Ok(idx) => Some(self.vec.remove(idx)),
-            Err(_) => None,
-        }
+        self.remove_by(|probe| element.compare(probe).reverse())
     }
 
     /// Returns `true` if the set contains an element.
     ///
-    /// The value may be any borrowed form of the set's element type,
-    /// but the ordering on the borrowed form *must* match the
-    /// ordering on the element type.
+    /// The value may be any borrowed form of the set's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html), but the ordering on that type *must*
+    /// match the ordering on the element type.
     ///
     /// # Examples
     ///
@@ -274,24 +503,22 @@ where
     /// ```
     pub fn contains<Q>(&self, element: &Q) -> bool
     where
-        A::Item: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<S::Item> + ?Sized,
     {
         self.find(element).is_ok()
     }
 
     /// Returns a reference to the element in the set, if any, that is equal to the given value.
     ///
-    /// The value may be any borrowed form of the set's element type,
-    /// but the ordering on the borrowed form *must* match the
-    /// ordering on the element type.
-    pub fn get<Q>(&self, element: &Q) -> Option<&A::Item>
+    /// The value may be any borrowed form of the set's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html), but the ordering on that type *must*
+    /// match the ordering on the element type.
+    pub fn get<Q>(&self, element: &Q) -> Option<&S::Item>
     where
-        A::Item: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<S::Item> + ?Sized,
     {
         match self.find(element) {
-            Ok(idx) => Some(&self.vec[idx]),
+            Ok(idx) => Some(&self.as_slice()[idx]),
             Err(_) => None,
         }
     }
@@ -299,16 +526,15 @@ where
     /// Returns a mutable reference to the element in the set, if any, that is equal to the given
     /// value. It is an error to mutate the element such that its ordering changes.
     ///
-    /// The value may be any borrowed form of the set's element type,
-    /// but the ordering on the borrowed form *must* match the
-    /// ordering on the element type.
-    pub fn get_mut<Q>(&mut self, element: &Q) -> Option<&mut A::Item>
+    /// The value may be any borrowed form of the set's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html), but the ordering on that type *must*
+    /// match the ordering on the element type.
+    pub fn get_mut<Q>(&mut self, element: &Q) -> Option<&mut S::Item>
     where
-        A::Item: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<S::Item> + ?Sized,
     {
         match self.find(element) {
-            Ok(idx) => Some(&mut self.vec[idx]),
+            Ok(idx) => Some(&mut self.storage.as_mut_slice()[idx]),
             Err(_) => None,
         }
     }
@@ -332,10 +558,9 @@ where
     /// assert_eq!(letters.get_value(&'u'), Some(&1));
     /// assert_eq!(letters.get_value(&'y'), None);
     /// ```
-    pub fn entry<Q>(&mut self, key: Q) -> Entry<A, Q>
+    pub fn entry<Q>(&mut self, key: Q) -> Entry<'_, S, Q>
     where
-        A::Item: Borrow<Q>,
-        Q: Ord,
+        Q: Comparable<S::Item>,
     {
         match self.find(&key) {
             Ok(idx) => Entry::occupied(self, idx),
@@ -343,88 +568,438 @@ where
         }
     }
 
+    /// Adds an element to the set if it is not already present, and returns a reference to the
+    /// element in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::<[u32; 4]>::new();
+    ///
+    /// assert_eq!(set.get_or_insert(2), &2);
+    /// assert_eq!(set.get_or_insert(2), &2);
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn get_or_insert(&mut self, element: S::Item) -> &S::Item {
+        self.entry(element).or_insert_key()
+    }
+
+    /// Returns a reference to the element in the set, if any, that is equal to the given value,
+    /// otherwise inserts the element computed by `f`.
+    ///
+    /// The value may be any borrowed form of the set's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html), but the ordering on that type *must*
+    /// match the ordering on the element type. The computed element must be equal to the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::<[String; 4]>::new();
+    ///
+    /// for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+    ///     set.get_or_insert_with(word, str::to_owned);
+    /// }
+    ///
+    /// assert_eq!(set.len(), 8);
+    /// ```
+    pub fn get_or_insert_with<Q, F>(&mut self, element: &Q, f: F) -> &S::Item
+    where
+        Q: Comparable<S::Item> + ?Sized,
+        F: FnOnce(&Q) -> S::Item,
+    {
+        let idx = match self.find(element) {
+            Ok(idx) => idx,
+            Err(idx) => {
+                let value = f(element);
+                debug_assert!(element.compare(&value) == Ordering::Equal);
+                self.storage.insert(idx, value);
+                idx
+            }
+        };
+        &self.as_slice()[idx]
+    }
+
+    /// Returns a cursor pointing to the first element that is above the given bound.
+    ///
+    /// If no such element exists then a cursor pointing to the "ghost" non-element is returned.
+    /// Passing `Bound::Unbounded` will return a cursor pointing to the first element of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let set = SmallOrdSet::from_buf([1, 2, 3, 4]);
+    ///
+    /// let cursor = set.lower_bound(Bound::Included(&2));
+    /// assert_eq!(cursor.current(), Some(&2));
+    /// let cursor = set.lower_bound(Bound::Excluded(&2));
+    /// assert_eq!(cursor.current(), Some(&3));
+    /// let cursor = set.lower_bound(Bound::Excluded(&4));
+    /// assert_eq!(cursor.current(), None);
+    /// ```
+    pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Cursor<'_, S>
+    where
+        Q: Comparable<S::Item> + ?Sized,
+    {
+        Cursor::new(self, self.lower_bound_index(bound))
+    }
+
+    /// Returns a mutable cursor pointing to the first element that is above the given bound.
+    ///
+    /// If no such element exists then a cursor pointing to the "ghost" non-element is returned.
+    /// Passing `Bound::Unbounded` will return a cursor pointing to the first element of the set.
+    pub fn lower_bound_mut<Q>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, S>
+    where
+        Q: Comparable<S::Item> + ?Sized,
+    {
+        let idx = self.lower_bound_index(bound);
+        CursorMut::new(self, idx)
+    }
+
+    /// Returns a cursor pointing to the last element that is below the given bound.
+    ///
+    /// If no such element exists then a cursor pointing to the "ghost" non-element is returned.
+    /// Passing `Bound::Unbounded` will return a cursor pointing to the last element of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let set = SmallOrdSet::from_buf([1, 2, 3, 4]);
+    ///
+    /// let cursor = set.upper_bound(Bound::Included(&3));
+    /// assert_eq!(cursor.current(), Some(&3));
+    /// let cursor = set.upper_bound(Bound::Excluded(&3));
+    /// assert_eq!(cursor.current(), Some(&2));
+    /// let cursor = set.upper_bound(Bound::Excluded(&1));
+    /// assert_eq!(cursor.current(), None);
+    /// ```
+    pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Cursor<'_, S>
+    where
+        Q: Comparable<S::Item> + ?Sized,
+    {
+        Cursor::new(self, self.upper_bound_index(bound))
+    }
+
+    /// Returns a mutable cursor pointing to the last element that is below the given bound.
+    ///
+    /// If no such element exists then a cursor pointing to the "ghost" non-element is returned.
+    /// Passing `Bound::Unbounded` will return a cursor pointing to the last element of the set.
+    pub fn upper_bound_mut<Q>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, S>
+    where
+        Q: Comparable<S::Item> + ?Sized,
+    {
+        let idx = self.upper_bound_index(bound);
+        CursorMut::new(self, idx)
+    }
+
+    fn lower_bound_index<Q>(&self, bound: Bound<&Q>) -> usize
+    where
+        Q: Comparable<S::Item> + ?Sized,
+    {
+        match bound {
+            Bound::Included(element) => match self.find(element) {
+                Ok(idx) | Err(idx) => idx,
+            },
+            Bound::Excluded(element) => match self.find(element) {
+                Ok(idx) => idx + 1,
+                Err(idx) => idx,
+            },
+            Bound::Unbounded => 0,
+        }
+    }
+
+    fn upper_bound_index<Q>(&self, bound: Bound<&Q>) -> usize
+    where
+        Q: Comparable<S::Item> + ?Sized,
+    {
+        let end = match bound {
+            Bound::Included(element) => match self.find(element) {
+                Ok(idx) => idx + 1,
+                Err(idx) => idx,
+            },
+            Bound::Excluded(element) => match self.find(element) {
+                Ok(idx) | Err(idx) => idx,
+            },
+            Bound::Unbounded => self.len(),
+        };
+        // The ghost element is represented by an index of `len`.
+        end.checked_sub(1).unwrap_or(self.len())
+    }
+
     fn find<Q>(&self, element: &Q) -> Result<usize, usize>
     where
-        A::Item: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Comparable<S::Item> + ?Sized,
     {
-        self.vec
-            .binary_search_by(|probe| Ord::cmp(probe.borrow(), element))
+        self.find_by(|probe| element.compare(probe).reverse())
     }
 
     fn sort_and_dedup(&mut self) {
-        self.vec.sort_unstable();
-        self.vec.dedup();
+        self.sort_and_dedup_by(Ord::cmp)
     }
 }
 
-impl<A: Array> AsRef<[A::Item]> for SmallOrdSet<A> {
-    fn as_ref(&self) -> &[A::Item] {
-        self.as_slice()
+impl<A: Array> SmallOrdSet<A> {
+    /// The number of elements the set can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity()
     }
-}
 
-impl<A: Array> Borrow<[A::Item]> for SmallOrdSet<A> {
-    fn borrow(&self) -> &[A::Item] {
-        self.as_slice()
+    /// Creates a draining iterator that removes the specified range in the set
+    /// and yields the removed items.
+    ///
+    /// Note 1: The element range is removed even if the iterator is only
+    /// partially consumed or not consumed at all.
+    ///
+    /// Note 2: It is unspecified how many elements are removed from the set
+    /// if the `Drain` value is leaked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the set.
+    pub fn drain<R>(&mut self, range: R) -> smallvec::Drain<'_, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.storage.drain(range)
+    }
+
+    /// Re-allocate to set the capacity to `max(new_cap, inline_size())`.
+    ///
+    /// Panics if `new_cap` is less than the set's length.
+    pub fn grow(&mut self, new_cap: usize) {
+        self.storage.grow(new_cap)
+    }
+
+    /// Re-allocate to set the capacity to `max(new_cap, inline_size())`, returning an error
+    /// instead of panicking or aborting if the allocation fails.
+    ///
+    /// Panics if `new_cap` is less than the set's length.
+    pub fn try_grow(&mut self, new_cap: usize) -> Result<(), CollectionAllocErr> {
+        self.storage.try_grow(new_cap)
+    }
+
+    /// The maximum number of elements this set can hold inline
+    pub fn inline_size(&self) -> usize {
+        self.storage.inline_size()
+    }
+
+    /// Convert the set into the inner `SmallVec`.
+    pub fn into_vec(self) -> SmallVec<A> {
+        self.storage
+    }
+
+    /// Reserve capacity for `additional` more elements to be inserted.
+    ///
+    /// May reserve more space to avoid frequent reallocations.
+    pub fn reserve(&mut self, additional: usize) {
+        self.storage.reserve(additional)
+    }
+
+    /// Reserve the minimum capacity for `additional` more elements to be inserted.
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.storage.reserve_exact(additional)
+    }
+
+    /// Reserve capacity for `additional` more elements to be inserted, returning an error if the
+    /// capacity overflows or the allocation fails.
+    ///
+    /// May reserve more space to avoid frequent reallocations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{CollectionAllocErr, SmallOrdSet};
+    ///
+    /// let mut set = SmallOrdSet::from_buf([1, 2]);
+    ///
+    /// assert!(set.try_reserve(8).is_ok());
+    /// assert!(matches!(
+    ///     set.try_reserve(usize::MAX),
+    ///     Err(CollectionAllocErr::CapacityOverflow)
+    /// ));
+    /// assert_eq!(set.as_slice(), &[1, 2]);
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.storage.try_reserve(additional)
+    }
+
+    /// Reserve the minimum capacity for `additional` more elements to be inserted, returning an
+    /// error if the capacity overflows or the allocation fails.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.storage.try_reserve_exact(additional)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    /// This method operates in place and preserves the order of the retained
+    /// elements.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut A::Item) -> bool,
+    {
+        self.storage.retain(f)
+    }
+
+    /// Construct a new [`SmallOrdSet`](type.SmallOrdSet.html) from a sorted `SmallVec`. `vec` must
+    /// be sorted and may not contain duplicate elements.
+    ///
+    /// # Safety
+    ///
+    /// Failure to uphold the restrictions on the `vec` parameter will not cause memory unsafety,
+    /// however the result of any operations on the resulting `SmallOrdSet` is unspecified.
+    pub fn from_vec_unchecked(vec: SmallVec<A>) -> Self {
+        SortedSet::from_storage_unchecked(vec)
     }
 }
 
-impl<A> Clone for SmallOrdSet<A>
+impl<A> SmallOrdSet<A>
 where
     A: Array,
-    A::Item: Clone,
+    A::Item: Ord,
 {
+    /// Moves all elements from `other` into `Self`, leaving other `empty`.
+    pub fn append(&mut self, other: &mut Self) {
+        self.extend(other.drain(..))
+    }
+
+    /// Construct a new [`SmallOrdSet`](type.SmallOrdSet.html) from a `SmallVec`. The vector will be
+    /// sorted and duplicate elements removed.
+    pub fn from_vec(vec: SmallVec<A>) -> Self {
+        SortedSet::from_storage(vec)
+    }
+
+    /// Constructs a new [`SmallOrdSet`](type.SmallOrdSet.html) on the stack from an `A` without
+    /// copying elements.
+    pub fn from_buf(buf: A) -> Self {
+        SmallOrdSet::from_vec(buf.into())
+    }
+
+    /// Adds all elements of the iterator to the set, returning an error instead of panicking or
+    /// aborting if space for them cannot be allocated.
+    ///
+    /// Space is reserved before each element is taken from the iterator, so no element is lost
+    /// on failure. If an error is returned, the elements consumed before the failure have been
+    /// added to the set, and the set remains sorted. Pass the iterator by reference to keep the
+    /// remaining elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::<[u32; 2]>::new();
+    ///
+    /// set.try_extend(vec![3, 1, 2, 1]).unwrap();
+    /// assert_eq!(set.as_slice(), &[1, 2, 3]);
+    ///
+    /// let mut huge = (4..).take(usize::MAX);
+    /// assert!(set.try_extend(huge.by_ref()).is_err());
+    /// assert_eq!(set.as_slice(), &[1, 2, 3]);
+    /// assert_eq!(huge.next(), Some(4));
+    /// ```
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), CollectionAllocErr>
+    where
+        I: IntoIterator<Item = A::Item>,
+    {
+        let mut iter = iter.into_iter();
+        let mut result = self.storage.try_reserve(iter.size_hint().0);
+        while result.is_ok() {
+            if self.storage.len() == self.storage.capacity() {
+                result = self.storage.try_reserve(1);
+                if result.is_err() {
+                    break;
+                }
+            }
+            match iter.next() {
+                Some(element) => self.storage.push(element),
+                None => break,
+            }
+        }
+        self.sort_and_dedup();
+        result
+    }
+}
+
+impl<S: SortedStorage> AsRef<[S::Item]> for SortedSet<S> {
+    fn as_ref(&self) -> &[S::Item] {
+        self.as_slice()
+    }
+}
+
+impl<S: SortedStorage> Borrow<[S::Item]> for SortedSet<S> {
+    fn borrow(&self) -> &[S::Item] {
+        self.as_slice()
+    }
+}
+
+impl<S: Clone> Clone for SortedSet<S> {
     fn clone(&self) -> Self {
-        SmallOrdSet::from_vec_unchecked(self.vec.clone())
+        SortedSet {
+            storage: self.storage.clone(),
+        }
     }
 
     fn clone_from(&mut self, source: &Self) {
-        self.vec.clone_from(&source.vec)
+        self.storage.clone_from(&source.storage)
     }
 }
 
-impl<A> Debug for SmallOrdSet<A>
+impl<S> Debug for SortedSet<S>
 where
-    A: Array,
-    A::Item: Debug,
+    S: SortedStorage,
+    S::Item: Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_set().entries(self.iter()).finish()
     }
 }
 
-impl<A: Array> Default for SmallOrdSet<A> {
+impl<S: Default> Default for SortedSet<S> {
     fn default() -> Self {
-        SmallOrdSet::from_vec_unchecked(Default::default())
+        SortedSet {
+            storage: S::default(),
+        }
     }
 }
 
-impl<A: Array> Deref for SmallOrdSet<A> {
-    type Target = [A::Item];
+impl<S: SortedStorage> Deref for SortedSet<S> {
+    type Target = [S::Item];
 
     fn deref(&self) -> &Self::Target {
         self.as_slice()
     }
 }
 
-impl<A> Eq for SmallOrdSet<A>
+impl<S> Eq for SortedSet<S>
 where
-    A: Array,
-    A::Item: Eq,
+    S: SortedStorage,
+    S::Item: Eq,
 {
 }
 
-impl<A> Extend<A::Item> for SmallOrdSet<A>
+impl<S> Extend<<S as SortedStorage>::Item> for SortedSet<S>
 where
-    A: Array,
-    A::Item: Ord,
+    S: SortedStorage + Extend<<S as SortedStorage>::Item>,
+    <S as SortedStorage>::Item: Ord,
 {
     fn extend<I>(&mut self, iter: I)
     where
-        I: IntoIterator<Item = A::Item>,
+        I: IntoIterator<Item = <S as SortedStorage>::Item>,
     {
-        self.vec.extend(iter);
+        self.storage.extend(iter);
         self.sort_and_dedup();
     }
 }
@@ -449,81 +1024,84 @@ where
     }
 }
 
-impl<A> FromIterator<A::Item> for SmallOrdSet<A>
+impl<S> FromIterator<<S as SortedStorage>::Item> for SortedSet<S>
 where
-    A: Array,
-    A::Item: Ord,
+    S: SortedStorage + FromIterator<<S as SortedStorage>::Item>,
+    <S as SortedStorage>::Item: Ord,
 {
-    fn from_iter<T>(iter: T) -> Self
+    fn from_iter<I>(iter: I) -> Self
     where
-        T: IntoIterator<Item = A::Item>,
+        I: IntoIterator<Item = <S as SortedStorage>::Item>,
     {
-        SmallOrdSet::from_vec(FromIterator::from_iter(iter))
+        SortedSet::from_storage(FromIterator::from_iter(iter))
     }
 }
 
-impl<A> Hash for SmallOrdSet<A>
+impl<S> Hash for SortedSet<S>
 where
-    A: Array,
-    A::Item: Hash,
+    S: SortedStorage,
+    S::Item: Hash,
 {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.vec.hash(state)
+        self.as_slice().hash(state)
     }
 }
 
-impl<A: Array, I: SliceIndex<[A::Item]>> Index<I> for SmallOrdSet<A> {
+impl<S: SortedStorage, I: SliceIndex<[S::Item]>> Index<I> for SortedSet<S> {
     type Output = I::Output;
 
     fn index(&self, index: I) -> &Self::Output {
-        self.vec.index(index)
+        self.as_slice().index(index)
     }
 }
 
-impl<A: Array> IntoIterator for SmallOrdSet<A> {
-    type IntoIter = smallvec::IntoIter<A>;
-    type Item = A::Item;
+impl<S> IntoIterator for SortedSet<S>
+where
+    S: SortedStorage + IntoIterator<Item = <S as SortedStorage>::Item>,
+{
+    type IntoIter = S::IntoIter;
+    type Item = <S as SortedStorage>::Item;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.vec.into_iter()
+        self.storage.into_iter()
     }
 }
 
-impl<'a, A: Array> IntoIterator for &'a SmallOrdSet<A> {
-    type IntoIter = slice::Iter<'a, A::Item>;
-    type Item = &'a A::Item;
+impl<'a, S: SortedStorage> IntoIterator for &'a SortedSet<S> {
+    type IntoIter = slice::Iter<'a, S::Item>;
+    type Item = &'a S::Item;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
     }
 }
 
-impl<A> Ord for SmallOrdSet<A>
+impl<S> Ord for SortedSet<S>
 where
-    A: Array,
-    A::Item: Ord,
+    S: SortedStorage,
+    S::Item: Ord,
 {
     fn cmp(&self, other: &Self) -> Ordering {
-        Ord::cmp(&self.vec, &other.vec)
+        Ord::cmp(self.as_slice(), other.as_slice())
     }
 }
 
-impl<A> PartialEq for SmallOrdSet<A>
+impl<S> PartialEq for SortedSet<S>
 where
-    A: Array,
-    A::Item: PartialEq,
+    S: SortedStorage,
+    S::Item: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
-        PartialEq::eq(&self.vec, &other.vec)
+        PartialEq::eq(self.as_slice(), other.as_slice())
     }
 }
 
-impl<A> PartialOrd for SmallOrdSet<A>
+impl<S> PartialOrd for SortedSet<S>
 where
-    A: Array,
-    A::Item: Ord,
+    S: SortedStorage,
+    S::Item: Ord,
 {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        PartialOrd::partial_cmp(&self.vec, &other.vec)
+        Some(self.cmp(other))
     }
 }
@@ -0,0 +1,139 @@
+/// Creates a [`SmallOrdSet`](type.SmallOrdSet.html) containing the given elements, with an inline
+/// size equal to the number of elements.
+///
+/// The elements are sorted and duplicates are removed. Alternatively, the `sorted:` form checks
+/// that the elements are already in strictly ascending order instead of sorting them, panicking
+/// otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::small_ord_set;
+///
+/// let set = small_ord_set![3, 1, 2, 1];
+/// assert_eq!(set.as_slice(), &[1, 2, 3]);
+/// assert_eq!(set.inline_size(), 4);
+///
+/// let set = small_ord_set![sorted: "a", "b", "c"];
+/// assert!(set.contains("b"));
+/// ```
+///
+/// ```should_panic
+/// use small_ord_set::small_ord_set;
+///
+/// let set = small_ord_set![sorted: 2, 1];
+/// ```
+#[macro_export]
+macro_rules! small_ord_set {
+    (sorted: $($elem:expr),* $(,)?) => {
+        $crate::__private::set_from_sorted_buf([$($elem),*])
+    };
+    ($($elem:expr),* $(,)?) => {
+        $crate::SmallOrdSet::from_buf([$($elem),*])
+    };
+}
+
+/// Creates a map, represented by a [`SmallOrdSet`](type.SmallOrdSet.html) of
+/// [`KeyValuePair`](struct.KeyValuePair.html)s, containing the given entries, with an inline size
+/// equal to the number of entries.
+///
+/// The entries are sorted by key. Alternatively, the `sorted:` form checks that the keys are
+/// already in strictly ascending order instead of sorting them.
+///
+/// # Panics
+///
+/// Panics if two entries have the same key, or if the `sorted:` form is used and the keys are not
+/// in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::small_ord_map;
+///
+/// let map = small_ord_map! {
+///     "b" => 2,
+///     "a" => 1,
+/// };
+/// assert_eq!(map.get_value("a"), Some(&1));
+/// assert_eq!(map.keys().collect::<Vec<_>>(), [&"a", &"b"]);
+///
+/// let map = small_ord_map! { sorted: 1 => 'x', 2 => 'y' };
+/// assert_eq!(map.get_value(&2), Some(&'y'));
+/// ```
+///
+/// ```should_panic
+/// use small_ord_set::small_ord_map;
+///
+/// let map = small_ord_map! { "a" => 1, "a" => 2 };
+/// ```
+#[macro_export]
+macro_rules! small_ord_map {
+    (sorted: $($key:expr => $value:expr),* $(,)?) => {
+        $crate::__private::map_from_sorted_buf([
+            $($crate::KeyValuePair { key: $key, value: $value }),*
+        ])
+    };
+    ($($key:expr => $value:expr),* $(,)?) => {
+        $crate::__private::map_from_buf([
+            $($crate::KeyValuePair { key: $key, value: $value }),*
+        ])
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use core::cmp::Ordering;
+
+    use smallvec::Array;
+
+    use crate::{KeyValuePair, SmallOrdSet};
+
+    pub fn set_from_sorted_buf<A>(buf: A) -> SmallOrdSet<A>
+    where
+        A: Array,
+        A::Item: Ord,
+    {
+        let set = SmallOrdSet::from_vec_unchecked(buf.into());
+        if set.windows(2).any(|w| w[0] >= w[1]) {
+            panic!("elements passed to `small_ord_set!` are not in strictly ascending order");
+        }
+        set
+    }
+
+    pub fn map_from_buf<A, K, V>(buf: A) -> SmallOrdSet<A>
+    where
+        A: Array<Item = KeyValuePair<K, V>>,
+        K: Ord,
+    {
+        let mut set = SmallOrdSet::from_vec_unchecked(buf.into());
+        set.storage.sort();
+        check_map_keys(&set);
+        set
+    }
+
+    pub fn map_from_sorted_buf<A, K, V>(buf: A) -> SmallOrdSet<A>
+    where
+        A: Array<Item = KeyValuePair<K, V>>,
+        K: Ord,
+    {
+        let set = SmallOrdSet::from_vec_unchecked(buf.into());
+        check_map_keys(&set);
+        set
+    }
+
+    fn check_map_keys<A, K, V>(set: &SmallOrdSet<A>)
+    where
+        A: Array<Item = KeyValuePair<K, V>>,
+        K: Ord,
+    {
+        for w in set.windows(2) {
+            match Ord::cmp(&w[0].key, &w[1].key) {
+                Ordering::Less => (),
+                Ordering::Equal => panic!("duplicate key passed to `small_ord_map!`"),
+                Ordering::Greater => {
+                    panic!("keys passed to `small_ord_map!` are not in ascending order")
+                }
+            }
+        }
+    }
+}
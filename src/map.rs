@@ -1,11 +1,12 @@
-use std::borrow::Borrow;
-use std::cmp::Ordering;
-use std::fmt::{self, Debug};
-use std::hash::{Hash, Hasher};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{Deref, DerefMut};
+use core::slice;
 
-use smallvec::Array;
-
-use crate::SmallOrdSet;
+use crate::{Comparable, EntryRef, SmallOrdSet, SortedSet, SortedStorage};
 
 /// A key-value pair. When used as the element type of a `SmallOrdSet`, it
 /// acts as a map.
@@ -20,21 +21,69 @@ pub struct KeyValuePair<K, V> {
     pub value: V,
 }
 
-impl<A, K, V> SmallOrdSet<A>
+/// A map represented by a [`SmallOrdSet`](type.SmallOrdSet.html) of key-value pairs, storing
+/// up to `N` entries inline.
+///
+/// This is a thin wrapper which dereferences to the underlying set, so all of its methods are
+/// available. Unlike the set, it is serialized as a map rather than a sequence of pairs when the
+/// `serde` feature is enabled.
+///
+/// Also unlike the set, maps are compared and hashed by both their keys and values.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::SmallOrdMap;
+///
+/// let mut map = SmallOrdMap::<char, u32, 8>::new();
+///
+/// *map.entry('a').or_insert(0) += 1;
+/// map.insert_value('b', 2);
+/// assert_eq!(map.get_value(&'a'), Some(&1));
+/// assert_eq!(map.keys().collect::<Vec<_>>(), [&'a', &'b']);
+///
+/// let other: SmallOrdMap<char, u32, 8> = vec![('b', 2), ('a', 2)].into_iter().collect();
+/// assert_ne!(map, other);
+///
+/// let copy: SmallOrdMap<char, u32, 8> = map.clone().into_iter().collect();
+/// assert_eq!(map, copy);
+/// ```
+pub struct SmallOrdMap<K, V, const N: usize> {
+    set: SmallOrdSet<[KeyValuePair<K, V>; N]>,
+}
+
+impl<K, V, const N: usize> SmallOrdMap<K, V, N> {
+    /// Make a new, empty, `SmallOrdMap`.
+    pub fn new() -> Self {
+        SmallOrdMap::from_set(SmallOrdSet::new())
+    }
+
+    /// Wrap a set of key-value pairs as a map.
+    pub fn from_set(set: SmallOrdSet<[KeyValuePair<K, V>; N]>) -> Self {
+        SmallOrdMap { set }
+    }
+
+    /// Convert the map into the underlying set of key-value pairs.
+    pub fn into_set(self) -> SmallOrdSet<[KeyValuePair<K, V>; N]> {
+        self.set
+    }
+}
+
+impl<S, K, V> SortedSet<S>
 where
-    A: Array<Item = KeyValuePair<K, V>>,
+    S: SortedStorage<Item = KeyValuePair<K, V>>,
     K: Ord,
 {
     /// Inserts a key-value pair into the map.
     ///
-    /// This function is a convenience wrapper around [`insert`](struct.SmallOrdSet.html#method.insert)
+    /// This function is a convenience wrapper around [`insert`](struct.SortedSet.html#method.insert)
     pub fn insert_value(&mut self, key: K, value: V) -> bool {
         self.insert(KeyValuePair { key, value })
     }
 
     /// Replaces a key-value pair in the map.
     ///
-    /// This function is a convenience wrapper around [`replace`](struct.SmallOrdSet.html#method.replace)
+    /// This function is a convenience wrapper around [`replace`](struct.SortedSet.html#method.replace)
     pub fn replace_value(&mut self, key: K, value: V) -> Option<V> {
         self.replace(KeyValuePair { key, value })
             .map(|kvp| kvp.value)
@@ -42,31 +91,89 @@ where
 
     /// Removes a key-value pair from the map.
     ///
-    /// This function is a convenience wrapper around [`remove`](struct.SmallOrdSet.html#method.remove)
-    pub fn remove_value(&mut self, key: &K) -> Option<V> {
-        self.remove(key).map(|kvp| kvp.value)
+    /// This function is a convenience wrapper around [`remove`](struct.SortedSet.html#method.remove).
+    /// The key may be any borrowed form of the map's key type, or any other type implementing
+    /// [`Comparable`](trait.Comparable.html).
+    pub fn remove_value<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.remove_by(|probe| key.compare(&probe.key).reverse())
+            .map(|kvp| kvp.value)
     }
 
     /// Gets a reference to the value for a key in the map.
     ///
-    /// This function is a convenience wrapper around [`get`](struct.SmallOrdSet.html#method.get)
-    pub fn get_value<'a>(&'a self, key: &K) -> Option<&'a V>
+    /// This function is a convenience wrapper around [`get`](struct.SortedSet.html#method.get).
+    /// The key may be any borrowed form of the map's key type, or any other type implementing
+    /// [`Comparable`](trait.Comparable.html).
+    pub fn get_value<'a, Q>(&'a self, key: &Q) -> Option<&'a V>
     where
         K: 'a,
+        Q: Comparable<K> + ?Sized,
     {
-        self.get(key).map(|kvp| &kvp.value)
+        match self.find_key(key) {
+            Ok(idx) => Some(&self.as_slice()[idx].value),
+            Err(_) => None,
+        }
     }
 
     /// Gets a mutable reference to the value for a key in the map.
     ///
-    /// This function is a convenience wrapper around [`get_mut`](struct.SmallOrdSet.html#method.get_mut).
+    /// This function is a convenience wrapper around [`get_mut`](struct.SortedSet.html#method.get_mut).
     /// Unlike `get_mut`, it prevents changing the order of elements by only returning the value part of
     /// the pair.
-    pub fn get_value_mut<'a>(&'a mut self, key: &K) -> Option<&'a mut V>
+    pub fn get_value_mut<'a, Q>(&'a mut self, key: &Q) -> Option<&'a mut V>
     where
         K: 'a,
+        Q: Comparable<K> + ?Sized,
     {
-        self.get_mut(key).map(|kvp| &mut kvp.value)
+        match self.find_key(key) {
+            Ok(idx) => Some(&mut self.storage.as_mut_slice()[idx].value),
+            Err(_) => None,
+        }
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation, looking it up
+    /// by a borrowed form of the key.
+    ///
+    /// Unlike [`entry`](#method.entry), the key is only converted into its owned form if a new
+    /// element is inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{SmallOrdSet, KeyValuePair};
+    ///
+    /// let mut words = SmallOrdSet::<[KeyValuePair<String, u32>; 8]>::new();
+    ///
+    /// for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+    ///     *words.entry_ref(word).or_insert(0) += 1;
+    /// }
+    ///
+    /// assert_eq!(words.get_value("the"), Some(&2));
+    /// assert_eq!(words.get_value("fox"), Some(&1));
+    /// ```
+    pub fn entry_ref<'b, Q>(&mut self, key: &'b Q) -> EntryRef<'_, 'b, S, Q>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.find_by(|probe| Ord::cmp(probe.key.borrow(), key)) {
+            Ok(idx) => EntryRef::occupied(self, idx),
+            Err(idx) => EntryRef::vacant(self, idx, key),
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the given key.
+    ///
+    /// The key may be any borrowed form of the map's key type, or any other type implementing
+    /// [`Comparable`](trait.Comparable.html).
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.find_key(key).is_ok()
     }
 
     /// Get an iterator over all keys in the map.
@@ -84,6 +191,13 @@ where
     {
         self.iter().map(|kvp| &kvp.value)
     }
+
+    fn find_key<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.find_by(|probe| key.compare(&probe.key).reverse())
+    }
 }
 
 impl<K: Hash, V> Hash for KeyValuePair<K, V> {
@@ -123,3 +237,134 @@ impl<K, V> Borrow<K> for KeyValuePair<K, V> {
         &self.key
     }
 }
+
+impl<K, V, const N: usize> Clone for SmallOrdMap<K, V, N>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallOrdMap::from_set(self.set.clone())
+    }
+}
+
+impl<K: Debug, V: Debug, const N: usize> Debug for SmallOrdMap<K, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.set.iter().map(|kvp| (&kvp.key, &kvp.value)))
+            .finish()
+    }
+}
+
+impl<K, V, const N: usize> Default for SmallOrdMap<K, V, N> {
+    fn default() -> Self {
+        SmallOrdMap::new()
+    }
+}
+
+impl<K, V, const N: usize> Deref for SmallOrdMap<K, V, N> {
+    type Target = SmallOrdSet<[KeyValuePair<K, V>; N]>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.set
+    }
+}
+
+impl<K, V, const N: usize> DerefMut for SmallOrdMap<K, V, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.set
+    }
+}
+
+impl<K: Eq, V: Eq, const N: usize> Eq for SmallOrdMap<K, V, N> {}
+
+impl<K: Ord, V, const N: usize> Extend<(K, V)> for SmallOrdMap<K, V, N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        self.set.extend(
+            iter.into_iter()
+                .map(|(key, value)| KeyValuePair { key, value }),
+        )
+    }
+}
+
+impl<K: Ord, V, const N: usize> Extend<KeyValuePair<K, V>> for SmallOrdMap<K, V, N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = KeyValuePair<K, V>>,
+    {
+        self.set.extend(iter)
+    }
+}
+
+impl<K, V, const N: usize> From<SmallOrdSet<[KeyValuePair<K, V>; N]>> for SmallOrdMap<K, V, N> {
+    fn from(set: SmallOrdSet<[KeyValuePair<K, V>; N]>) -> Self {
+        SmallOrdMap::from_set(set)
+    }
+}
+
+impl<K, V, const N: usize> From<SmallOrdMap<K, V, N>> for SmallOrdSet<[KeyValuePair<K, V>; N]> {
+    fn from(map: SmallOrdMap<K, V, N>) -> Self {
+        map.into_set()
+    }
+}
+
+impl<K: Ord, V, const N: usize> FromIterator<(K, V)> for SmallOrdMap<K, V, N> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = SmallOrdMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V, const N: usize> FromIterator<KeyValuePair<K, V>> for SmallOrdMap<K, V, N> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = KeyValuePair<K, V>>,
+    {
+        SmallOrdMap::from_set(FromIterator::from_iter(iter))
+    }
+}
+
+impl<K: Hash, V: Hash, const N: usize> Hash for SmallOrdMap<K, V, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for kvp in self.iter() {
+            kvp.key.hash(state);
+            kvp.value.hash(state);
+        }
+    }
+}
+
+impl<K, V, const N: usize> IntoIterator for SmallOrdMap<K, V, N> {
+    type IntoIter = smallvec::IntoIter<[KeyValuePair<K, V>; N]>;
+    type Item = KeyValuePair<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.into_iter()
+    }
+}
+
+impl<'a, K, V, const N: usize> IntoIterator for &'a SmallOrdMap<K, V, N> {
+    type IntoIter = slice::Iter<'a, KeyValuePair<K, V>>;
+    type Item = &'a KeyValuePair<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.iter()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, const N: usize> PartialEq for SmallOrdMap<K, V, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(lhs, rhs)| lhs.key == rhs.key && lhs.value == rhs.value)
+    }
+}
@@ -0,0 +1,262 @@
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+
+use crate::{Comparable, Entry, KeyValuePair, SmallOrdSet};
+
+/// A map from keys to small sets of values, represented by a sorted `SmallVec` of keys, each
+/// paired with a [`SmallOrdSet`](type.SmallOrdSet.html) of values.
+///
+/// Up to `N` keys are stored inline, and up to `M` values are stored inline for each key. Keys are
+/// removed automatically when their last value is removed, so every key in the map has at least
+/// one value.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::SmallOrdMultiMap;
+///
+/// let mut deps = SmallOrdMultiMap::<&str, &str, 4, 2>::new();
+/// deps.insert("app", "log");
+/// deps.insert("app", "http");
+/// deps.insert("http", "log");
+///
+/// assert_eq!(deps.get_all("app"), &["http", "log"]);
+/// assert!(deps.contains_pair("http", "log"));
+///
+/// deps.remove("http", "log");
+/// assert!(!deps.contains_key("http"));
+///
+/// let pairs: Vec<_> = deps.iter().collect();
+/// assert_eq!(pairs, [(&"app", &"http"), (&"app", &"log")]);
+/// ```
+pub struct SmallOrdMultiMap<K, V, const N: usize, const M: usize> {
+    map: Inner<K, V, N, M>,
+}
+
+type Inner<K, V, const N: usize, const M: usize> =
+    SmallOrdSet<[KeyValuePair<K, SmallOrdSet<[V; M]>>; N]>;
+
+impl<K, V, const N: usize, const M: usize> SmallOrdMultiMap<K, V, N, M> {
+    /// Make a new, empty, `SmallOrdMultiMap`.
+    pub fn new() -> Self {
+        SmallOrdMultiMap::default()
+    }
+
+    /// Get a reference to the underlying map from keys to sets of values.
+    pub fn as_map(&self) -> &Inner<K, V, N, M> {
+        &self.map
+    }
+
+    /// Remove all keys and values from the map.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// The number of distinct keys in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// The number of key-value pairs in the map.
+    pub fn total_len(&self) -> usize {
+        self.map.iter().map(|kvp| kvp.value.len()).sum()
+    }
+
+    /// Returns `true` if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Get an iterator over all key-value pairs in the map, in lexicographic order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + Clone {
+        self.map
+            .iter()
+            .flat_map(|kvp| kvp.value.iter().map(move |value| (&kvp.key, value)))
+    }
+
+    /// Get an iterator over all distinct keys in the map.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + Clone {
+        self.map.iter().map(|kvp| &kvp.key)
+    }
+}
+
+impl<K, V, const N: usize, const M: usize> SmallOrdMultiMap<K, V, N, M>
+where
+    K: Ord,
+    V: Ord,
+{
+    /// Adds a key-value pair to the map.
+    ///
+    /// Returns `true` if the map did not already contain the pair.
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        match self.map.entry(key) {
+            Entry::Occupied(mut entry) => entry.get_mut().insert(value),
+            Entry::Vacant(entry) => {
+                let mut values = SmallOrdSet::new();
+                values.insert(value);
+                entry.insert(values);
+                true
+            }
+        }
+    }
+
+    /// Removes a key-value pair from the map. If it was the last value for the key, the key is
+    /// removed as well.
+    ///
+    /// Returns `true` if the map contained the pair.
+    pub fn remove<Q, R>(&mut self, key: &Q, value: &R) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+        R: Comparable<V> + ?Sized,
+    {
+        let idx = match self.find(key) {
+            Ok(idx) => idx,
+            Err(_) => return false,
+        };
+        let values = &mut self.map.storage[idx].value;
+        if values.remove(value).is_none() {
+            return false;
+        }
+        if values.is_empty() {
+            self.map.storage.remove(idx);
+        }
+        true
+    }
+
+    /// Removes a key and all of its values from the map, returning the values.
+    pub fn remove_all<Q>(&mut self, key: &Q) -> Option<SmallOrdSet<[V; M]>>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.map.remove_value(key)
+    }
+
+    /// Gets the values for a key in the map, in ascending order. If the key is not present, an
+    /// empty slice is returned.
+    pub fn get_all<Q>(&self, key: &Q) -> &[V]
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        match self.map.get_value(key) {
+            Some(values) => values.as_slice(),
+            None => &[],
+        }
+    }
+
+    /// Returns `true` if the map contains any values for the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Returns `true` if the map contains the given key-value pair.
+    pub fn contains_pair<Q, R>(&self, key: &Q, value: &R) -> bool
+    where
+        Q: Comparable<K> + ?Sized,
+        R: Comparable<V> + ?Sized,
+    {
+        match self.map.get_value(key) {
+            Some(values) => values.contains(value),
+            None => false,
+        }
+    }
+
+    fn find<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        Q: Comparable<K> + ?Sized,
+    {
+        self.map.find_by(|probe| key.compare(&probe.key).reverse())
+    }
+}
+
+impl<K, V, const N: usize, const M: usize> Clone for SmallOrdMultiMap<K, V, N, M>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallOrdMultiMap {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<K, V, const N: usize, const M: usize> Debug for SmallOrdMultiMap<K, V, N, M>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.map.iter().map(|kvp| (&kvp.key, &kvp.value)))
+            .finish()
+    }
+}
+
+impl<K, V, const N: usize, const M: usize> Default for SmallOrdMultiMap<K, V, N, M> {
+    fn default() -> Self {
+        SmallOrdMultiMap {
+            map: SmallOrdSet::default(),
+        }
+    }
+}
+
+impl<K: Eq, V: Eq, const N: usize, const M: usize> Eq for SmallOrdMultiMap<K, V, N, M> {}
+
+impl<K, V, const N: usize, const M: usize> Extend<(K, V)> for SmallOrdMultiMap<K, V, N, M>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V, const N: usize, const M: usize> FromIterator<(K, V)> for SmallOrdMultiMap<K, V, N, M>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = SmallOrdMultiMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash, V: Hash, const N: usize, const M: usize> Hash for SmallOrdMultiMap<K, V, N, M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.map.len().hash(state);
+        for kvp in &self.map {
+            kvp.key.hash(state);
+            kvp.value.hash(state);
+        }
+    }
+}
+
+impl<K, V, const N: usize, const M: usize> PartialEq for SmallOrdMultiMap<K, V, N, M>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.map.len() == other.map.len()
+            && self
+                .map
+                .iter()
+                .zip(&other.map)
+                .all(|(l, r)| l.key == r.key && l.value == r.value)
+    }
+}
@@ -0,0 +1,453 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::num::NonZeroUsize;
+use core::slice;
+
+use smallvec::Array;
+
+use crate::{Comparable, Entry, KeyValuePair, SmallOrdSet};
+
+/// A multiset represented by a sorted `SmallVec` of elements and their multiplicities.
+///
+/// Each distinct element is stored once, as a [`KeyValuePair`](struct.KeyValuePair.html) of the
+/// element and the number of times it occurs.
+///
+/// # Examples
+///
+/// ```
+/// use std::num::NonZeroUsize;
+/// use small_ord_set::{KeyValuePair, SmallOrdMultiSet};
+///
+/// let mut bag = SmallOrdMultiSet::<[KeyValuePair<char, NonZeroUsize>; 4]>::new();
+///
+/// for ch in "banana".chars() {
+///     bag.insert(ch);
+/// }
+///
+/// assert_eq!(bag.count(&'a'), 3);
+/// assert_eq!(bag.count(&'n'), 2);
+/// assert_eq!(bag.len(), 3);
+/// assert_eq!(bag.total_len(), 6);
+/// assert_eq!(bag.iter().len(), 6);
+/// assert_eq!(bag.iter().collect::<String>(), "aaabnn");
+/// ```
+pub struct SmallOrdMultiSet<A: Array> {
+    set: SmallOrdSet<A>,
+}
+
+/// An iterator over the elements of a [`SmallOrdMultiSet`], repeating each element by its
+/// multiplicity.
+///
+/// This `struct` is constructed from the [`iter`] method on [`SmallOrdMultiSet`].
+///
+/// [`SmallOrdMultiSet`]: struct.SmallOrdMultiSet.html
+/// [`iter`]: struct.SmallOrdMultiSet.html#method.iter
+pub struct MultiSetIter<'a, T> {
+    iter: slice::Iter<'a, KeyValuePair<T, NonZeroUsize>>,
+    current: Option<&'a T>,
+    remaining: usize,
+    len: usize,
+}
+
+impl<A, T> SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+{
+    /// Make a new, empty, `SmallOrdMultiSet`.
+    pub fn new() -> Self {
+        SmallOrdMultiSet::default()
+    }
+
+    /// Get a reference to the underlying set of elements and their multiplicities.
+    pub fn as_set(&self) -> &SmallOrdSet<A> {
+        &self.set
+    }
+
+    /// Convert the multiset into the underlying set of elements and their multiplicities.
+    pub fn into_set(self) -> SmallOrdSet<A> {
+        self.set
+    }
+
+    /// Remove all elements from the multiset.
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    /// The number of distinct elements in the multiset.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// The number of elements in the multiset, counting multiplicities.
+    pub fn total_len(&self) -> usize {
+        self.set.iter().map(|kvp| kvp.value.get()).sum()
+    }
+
+    /// Returns `true` if the multiset is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Construct an iterator over the multiset, in ascending order. Each element is repeated as
+    /// many times as it occurs in the multiset.
+    pub fn iter(&self) -> MultiSetIter<'_, T> {
+        MultiSetIter {
+            iter: self.set.iter(),
+            current: None,
+            remaining: 0,
+            len: self.total_len(),
+        }
+    }
+
+    /// Construct an iterator over the distinct elements of the multiset and their multiplicities,
+    /// in ascending order.
+    pub fn counts<'a>(&'a self) -> impl Iterator<Item = (&'a T, NonZeroUsize)> + Clone
+    where
+        T: 'a,
+    {
+        self.set.iter().map(|kvp| (&kvp.key, kvp.value))
+    }
+}
+
+impl<A, T> SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+    T: Ord,
+{
+    /// Construct a new [`SmallOrdMultiSet`](struct.SmallOrdMultiSet.html) from a set of elements
+    /// and their multiplicities.
+    pub fn from_set(set: SmallOrdSet<A>) -> Self {
+        SmallOrdMultiSet { set }
+    }
+
+    /// Adds an occurrence of an element to the multiset, and returns its new multiplicity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the multiplicity of the element overflows `usize`.
+    pub fn insert(&mut self, element: T) -> usize {
+        self.insert_n(element, NonZeroUsize::new(1).unwrap())
+    }
+
+    /// Adds `n` occurrences of an element to the multiset, and returns its new multiplicity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the multiplicity of the element overflows `usize`.
+    pub fn insert_n(&mut self, element: T, n: NonZeroUsize) -> usize {
+        match self.set.entry(element) {
+            Entry::Occupied(mut entry) => {
+                let count = entry.get_mut();
+                *count = count.checked_add(n.get()).expect("multiplicity overflow");
+                count.get()
+            }
+            Entry::Vacant(entry) => entry.insert(n).get(),
+        }
+    }
+
+    /// Removes a single occurrence of an element from the multiset. Returns `true` if the element
+    /// was present.
+    ///
+    /// The element may be any borrowed form of the multiset's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html).
+    pub fn remove_one<Q>(&mut self, element: &Q) -> bool
+    where
+        Q: Comparable<T> + ?Sized,
+    {
+        match self.find(element) {
+            Ok(idx) => {
+                match NonZeroUsize::new(self.set.storage[idx].value.get() - 1) {
+                    Some(count) => self.set.storage[idx].value = count,
+                    None => {
+                        self.set.storage.remove(idx);
+                    }
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Removes all occurrences of an element from the multiset, and returns the number of
+    /// occurrences removed.
+    ///
+    /// The element may be any borrowed form of the multiset's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html).
+    pub fn remove_all<Q>(&mut self, element: &Q) -> usize
+    where
+        Q: Comparable<T> + ?Sized,
+    {
+        match self.find(element) {
+            Ok(idx) => self.set.storage.remove(idx).value.get(),
+            Err(_) => 0,
+        }
+    }
+
+    /// Returns the number of times the element occurs in the multiset.
+    ///
+    /// The element may be any borrowed form of the multiset's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html).
+    pub fn count<Q>(&self, element: &Q) -> usize
+    where
+        Q: Comparable<T> + ?Sized,
+    {
+        match self.find(element) {
+            Ok(idx) => self.set.storage[idx].value.get(),
+            Err(_) => 0,
+        }
+    }
+
+    /// Returns `true` if the multiset contains at least one occurrence of the element.
+    ///
+    /// The element may be any borrowed form of the multiset's element type, or any other type
+    /// implementing [`Comparable`](trait.Comparable.html).
+    pub fn contains<Q>(&self, element: &Q) -> bool
+    where
+        Q: Comparable<T> + ?Sized,
+    {
+        self.find(element).is_ok()
+    }
+
+    /// Returns the union of two multisets. The multiplicity of each element is the maximum of its
+    /// multiplicities in `self` and `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroUsize;
+    /// use small_ord_set::{KeyValuePair, SmallOrdMultiSet};
+    ///
+    /// let a: SmallOrdMultiSet<[KeyValuePair<char, NonZeroUsize>; 4]> = "aab".chars().collect();
+    /// let b: SmallOrdMultiSet<[KeyValuePair<char, NonZeroUsize>; 4]> = "abbc".chars().collect();
+    ///
+    /// assert_eq!(a.union(&b).iter().collect::<String>(), "aabbc");
+    /// assert_eq!(a.intersection(&b).iter().collect::<String>(), "ab");
+    /// assert_eq!(a.sum(&b).iter().collect::<String>(), "aaabbbc");
+    /// ```
+    pub fn union(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.merge(other, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        })
+    }
+
+    /// Returns the intersection of two multisets. The multiplicity of each element is the minimum
+    /// of its multiplicities in `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.merge(other, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            _ => None,
+        })
+    }
+
+    /// Returns the sum of two multisets. The multiplicity of each element is the sum of its
+    /// multiplicities in `self` and `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the multiplicity of an element overflows `usize`.
+    pub fn sum(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.merge(other, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(a.checked_add(b.get()).expect("multiplicity overflow")),
+            (a, b) => a.or(b),
+        })
+    }
+
+    fn merge<F>(&self, other: &Self, mut f: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(Option<NonZeroUsize>, Option<NonZeroUsize>) -> Option<NonZeroUsize>,
+    {
+        let mut result = SmallOrdSet::new();
+        let mut lhs = self.set.iter().peekable();
+        let mut rhs = other.set.iter().peekable();
+        loop {
+            let (key, l, r) = match (lhs.peek().copied(), rhs.peek().copied()) {
+                (Some(l), Some(r)) => match Ord::cmp(&l.key, &r.key) {
+                    Ordering::Less => (&l.key, Some(l.value), None),
+                    Ordering::Equal => (&l.key, Some(l.value), Some(r.value)),
+                    Ordering::Greater => (&r.key, None, Some(r.value)),
+                },
+                (Some(l), None) => (&l.key, Some(l.value), None),
+                (None, Some(r)) => (&r.key, None, Some(r.value)),
+                (None, None) => break,
+            };
+            if let Some(count) = f(l, r) {
+                result.storage.push(KeyValuePair {
+                    key: key.clone(),
+                    value: count,
+                });
+            }
+            if l.is_some() {
+                lhs.next();
+            }
+            if r.is_some() {
+                rhs.next();
+            }
+        }
+        SmallOrdMultiSet::from_set(result)
+    }
+
+    fn find<Q>(&self, element: &Q) -> Result<usize, usize>
+    where
+        Q: Comparable<T> + ?Sized,
+    {
+        self.set
+            .find_by(|probe| element.compare(&probe.key).reverse())
+    }
+}
+
+impl<'a, T> Iterator for MultiSetIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            let kvp = self.iter.next()?;
+            self.current = Some(&kvp.key);
+            self.remaining = kvp.value.get();
+        }
+        self.remaining -= 1;
+        self.len -= 1;
+        self.current
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for MultiSetIter<'_, T> {}
+
+impl<T> FusedIterator for MultiSetIter<'_, T> {}
+
+impl<T> Clone for MultiSetIter<'_, T> {
+    fn clone(&self) -> Self {
+        MultiSetIter {
+            iter: self.iter.clone(),
+            current: self.current,
+            remaining: self.remaining,
+            len: self.len,
+        }
+    }
+}
+
+impl<A> Clone for SmallOrdMultiSet<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallOrdMultiSet {
+            set: self.set.clone(),
+        }
+    }
+}
+
+impl<A, T> Debug for SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.counts()).finish()
+    }
+}
+
+impl<T: Debug> Debug for MultiSetIter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<A: Array> Default for SmallOrdMultiSet<A> {
+    fn default() -> Self {
+        SmallOrdMultiSet {
+            set: SmallOrdSet::default(),
+        }
+    }
+}
+
+impl<A, T> Eq for SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+    T: Eq,
+{
+}
+
+impl<A, T> Extend<T> for SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+    T: Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for element in iter {
+            self.insert(element);
+        }
+    }
+}
+
+impl<A, T> FromIterator<T> for SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+    T: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut set = SmallOrdMultiSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<A, T> Hash for SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.set.len().hash(state);
+        for (element, count) in self.counts() {
+            element.hash(state);
+            count.hash(state);
+        }
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+    T: 'a,
+{
+    type IntoIter = MultiSetIter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<A, T> PartialEq for SmallOrdMultiSet<A>
+where
+    A: Array<Item = KeyValuePair<T, NonZeroUsize>>,
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.counts().eq(other.counts())
+    }
+}
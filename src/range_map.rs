@@ -0,0 +1,370 @@
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::Range;
+
+use crate::{KeyValuePair, SmallOrdSet};
+
+/// A map from disjoint half-open ranges of keys to values, represented by a `SmallOrdSet` of
+/// range start points.
+///
+/// Inserting a range overwrites any overlapping parts of existing ranges. Up to `N` ranges are
+/// stored inline.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::SmallRangeMap;
+///
+/// let mut regions = SmallRangeMap::<u32, &str, 4>::new();
+/// regions.insert_range(0x0000..0x4000, "rom");
+/// regions.insert_range(0x4000..0x8000, "ram");
+/// regions.insert_range(0x3000..0x5000, "io");
+///
+/// assert_eq!(regions.get(&0x2fff), Some(&"rom"));
+/// assert_eq!(regions.get(&0x4fff), Some(&"io"));
+/// assert_eq!(regions.get(&0x8000), None);
+///
+/// let ranges: Vec<_> = regions.iter().collect();
+/// assert_eq!(
+///     ranges,
+///     [(0x0000..0x3000, &"rom"), (0x3000..0x5000, &"io"), (0x5000..0x8000, &"ram")]
+/// );
+/// ```
+pub struct SmallRangeMap<K, V, const N: usize> {
+    ranges: SmallOrdSet<[KeyValuePair<K, Segment<K, V>>; N]>,
+}
+
+struct Segment<K, V> {
+    end: K,
+    value: V,
+}
+
+impl<K, V, const N: usize> SmallRangeMap<K, V, N> {
+    /// Make a new, empty, `SmallRangeMap`.
+    pub fn new() -> Self {
+        SmallRangeMap::default()
+    }
+
+    /// Remove all ranges from the map.
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// The number of disjoint ranges in the map.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Returns `true` if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Get an iterator over all values in the map, in ascending order of their ranges.
+    pub fn values(&self) -> impl Iterator<Item = &V> + Clone {
+        self.ranges.iter().map(|kvp| &kvp.value.value)
+    }
+}
+
+impl<K, V, const N: usize> SmallRangeMap<K, V, N>
+where
+    K: Ord + Clone,
+{
+    /// Get an iterator over the disjoint ranges in the map and their values, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (Range<K>, &V)> + Clone {
+        self.ranges
+            .iter()
+            .map(|kvp| (kvp.key.clone()..kvp.value.end.clone(), &kvp.value.value))
+    }
+
+    /// Gets a reference to the value of the range containing the given point.
+    pub fn get(&self, point: &K) -> Option<&V> {
+        self.find(point).map(|idx| &self.ranges[idx].value.value)
+    }
+
+    /// Gets the range containing the given point, and a reference to its value.
+    pub fn get_range(&self, point: &K) -> Option<(Range<K>, &V)> {
+        self.find(point).map(|idx| {
+            let kvp = &self.ranges[idx];
+            (kvp.key.clone()..kvp.value.end.clone(), &kvp.value.value)
+        })
+    }
+
+    /// Returns `true` if any range in the map contains the given point.
+    pub fn contains(&self, point: &K) -> bool {
+        self.find(point).is_some()
+    }
+
+    /// Maps a range to a value, overwriting the parts of any existing ranges which overlap it.
+    ///
+    /// Empty ranges are ignored.
+    pub fn insert_range(&mut self, range: Range<K>, value: V)
+    where
+        V: Clone,
+    {
+        if range.is_empty() {
+            return;
+        }
+
+        let idx = self.remove_range_at(&range);
+        self.ranges.storage.insert(
+            idx,
+            KeyValuePair {
+                key: range.start,
+                value: Segment {
+                    end: range.end,
+                    value,
+                },
+            },
+        );
+    }
+
+    /// Maps a range to a value, overwriting the parts of any existing ranges which overlap it.
+    /// The range is then merged with any adjacent ranges with an equal value.
+    ///
+    /// Empty ranges are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallRangeMap;
+    ///
+    /// let mut map = SmallRangeMap::<u32, char, 4>::new();
+    /// map.insert_range_coalesce(0..10, 'a');
+    /// map.insert_range_coalesce(20..30, 'a');
+    /// map.insert_range_coalesce(10..20, 'a');
+    ///
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), [(0..30, &'a')]);
+    ///
+    /// // Adjacent ranges with a different value are left separate.
+    /// map.insert_range_coalesce(30..40, 'b');
+    /// map.insert_range_coalesce(5..15, 'c');
+    /// assert_eq!(
+    ///     map.iter().collect::<Vec<_>>(),
+    ///     [(0..5, &'a'), (5..15, &'c'), (15..30, &'a'), (30..40, &'b')],
+    /// );
+    /// ```
+    pub fn insert_range_coalesce(&mut self, range: Range<K>, value: V)
+    where
+        V: Clone + PartialEq,
+    {
+        if range.is_empty() {
+            return;
+        }
+
+        let mut idx = self.remove_range_at(&range);
+        let mut segment = KeyValuePair {
+            key: range.start,
+            value: Segment {
+                end: range.end,
+                value,
+            },
+        };
+        if idx > 0 {
+            let prev = &self.ranges[idx - 1];
+            if prev.value.end == segment.key && prev.value.value == segment.value.value {
+                segment.key = self.ranges.storage.remove(idx - 1).key;
+                idx -= 1;
+            }
+        }
+        if idx < self.ranges.len() {
+            let next = &self.ranges[idx];
+            if segment.value.end == next.key && segment.value.value == next.value.value {
+                segment.value.end = self.ranges.storage.remove(idx).value.end;
+            }
+        }
+        self.ranges.storage.insert(idx, segment);
+    }
+
+    /// Removes a range from the map, splitting any ranges which partially overlap it.
+    ///
+    /// Empty ranges are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallRangeMap;
+    ///
+    /// let mut map = SmallRangeMap::<u32, char, 4>::new();
+    /// map.insert_range(0..10, 'a');
+    /// map.insert_range(10..20, 'b');
+    ///
+    /// map.remove_range(3..6);
+    /// assert_eq!(
+    ///     map.iter().collect::<Vec<_>>(),
+    ///     [(0..3, &'a'), (6..10, &'a'), (10..20, &'b')],
+    /// );
+    /// assert_eq!(map.get(&5), None);
+    /// assert_eq!(map.get(&6), Some(&'a'));
+    ///
+    /// map.remove_range(8..12);
+    /// assert_eq!(
+    ///     map.iter().collect::<Vec<_>>(),
+    ///     [(0..3, &'a'), (6..8, &'a'), (12..20, &'b')],
+    /// );
+    /// ```
+    pub fn remove_range(&mut self, range: Range<K>)
+    where
+        V: Clone,
+    {
+        if range.is_empty() {
+            return;
+        }
+
+        self.remove_range_at(&range);
+    }
+
+    // Removes the range from the map, and returns the index at which it would be inserted.
+    fn remove_range_at(&mut self, range: &Range<K>) -> usize
+    where
+        V: Clone,
+    {
+        let lo = self.ranges.partition_point(|r| r.value.end <= range.start);
+        let hi = self.ranges.partition_point(|r| r.key < range.end);
+        if lo == hi {
+            return lo;
+        }
+
+        let first = &self.ranges[lo];
+        let left = if first.key < range.start {
+            Some(KeyValuePair {
+                key: first.key.clone(),
+                value: Segment {
+                    end: range.start.clone(),
+                    value: first.value.value.clone(),
+                },
+            })
+        } else {
+            None
+        };
+        let last = &self.ranges[hi - 1];
+        let right = if last.value.end > range.end {
+            Some(KeyValuePair {
+                key: range.end.clone(),
+                value: Segment {
+                    end: last.value.end.clone(),
+                    value: last.value.value.clone(),
+                },
+            })
+        } else {
+            None
+        };
+
+        let idx = if left.is_some() { lo + 1 } else { lo };
+        self.ranges.drain(lo..hi);
+        self.ranges
+            .storage
+            .insert_many(lo, left.into_iter().chain(right));
+        idx
+    }
+
+    fn find(&self, point: &K) -> Option<usize> {
+        match self.ranges.partition_point(|r| r.key <= *point) {
+            0 => None,
+            idx if *point < self.ranges[idx - 1].value.end => Some(idx - 1),
+            _ => None,
+        }
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for Segment<K, V> {
+    fn clone(&self) -> Self {
+        Segment {
+            end: self.end.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<K, V, const N: usize> Clone for SmallRangeMap<K, V, N>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallRangeMap {
+            ranges: self.ranges.clone(),
+        }
+    }
+}
+
+impl<K, V, const N: usize> Debug for SmallRangeMap<K, V, N>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.ranges
+                    .iter()
+                    .map(|kvp| (&kvp.key..&kvp.value.end, &kvp.value.value)),
+            )
+            .finish()
+    }
+}
+
+impl<K, V, const N: usize> Default for SmallRangeMap<K, V, N> {
+    fn default() -> Self {
+        SmallRangeMap {
+            ranges: SmallOrdSet::default(),
+        }
+    }
+}
+
+impl<K: Eq, V: Eq, const N: usize> Eq for SmallRangeMap<K, V, N> {}
+
+impl<K, V, const N: usize> Extend<(Range<K>, V)> for SmallRangeMap<K, V, N>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Range<K>, V)>,
+    {
+        for (range, value) in iter {
+            self.insert_range(range, value);
+        }
+    }
+}
+
+impl<K, V, const N: usize> FromIterator<(Range<K>, V)> for SmallRangeMap<K, V, N>
+where
+    K: Ord + Clone,
+    V: Clone,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Range<K>, V)>,
+    {
+        let mut map = SmallRangeMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash, V: Hash, const N: usize> Hash for SmallRangeMap<K, V, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ranges.len().hash(state);
+        for kvp in &self.ranges {
+            kvp.key.hash(state);
+            kvp.value.end.hash(state);
+            kvp.value.value.hash(state);
+        }
+    }
+}
+
+impl<K, V, const N: usize> PartialEq for SmallRangeMap<K, V, N>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ranges.len() == other.ranges.len()
+            && self.ranges.iter().zip(&other.ranges).all(|(l, r)| {
+                l.key == r.key && l.value.end == r.value.end && l.value.value == r.value.value
+            })
+    }
+}
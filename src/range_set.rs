@@ -0,0 +1,397 @@
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::Range;
+use core::slice;
+
+use crate::{KeyValuePair, SmallOrdSet};
+
+/// A set of disjoint half-open ranges, represented by a `SmallOrdSet` of start and end points.
+///
+/// Overlapping and adjacent ranges are merged as they are inserted, so the set always holds the
+/// minimal number of ranges. Up to `N` ranges are stored inline.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::SmallRangeSet;
+///
+/// let mut ports = SmallRangeSet::<u16, 4>::new();
+/// ports.insert_range(8000..8010);
+/// ports.insert_range(8010..8020);
+/// ports.insert_range(9000..9001);
+/// assert_eq!(ports.iter().collect::<Vec<_>>(), [8000..8020, 9000..9001]);
+///
+/// ports.remove_range(8005..8015);
+/// assert_eq!(ports.iter().collect::<Vec<_>>(), [8000..8005, 8015..8020, 9000..9001]);
+///
+/// assert!(ports.contains(&8004));
+/// assert!(!ports.contains(&8005));
+/// assert!(ports.covers(8015..8020));
+/// ```
+pub struct SmallRangeSet<T, const N: usize> {
+    ranges: SmallOrdSet<[KeyValuePair<T, T>; N]>,
+}
+
+/// An iterator over the gaps between the ranges of a [`SmallRangeSet`].
+///
+/// This `struct` is constructed from the [`gaps`] method on [`SmallRangeSet`].
+///
+/// [`SmallRangeSet`]: struct.SmallRangeSet.html
+/// [`gaps`]: struct.SmallRangeSet.html#method.gaps
+pub struct Gaps<'a, T> {
+    iter: slice::Iter<'a, KeyValuePair<T, T>>,
+    start: T,
+    end: T,
+}
+
+impl<T, const N: usize> SmallRangeSet<T, N> {
+    /// Make a new, empty, `SmallRangeSet`.
+    pub fn new() -> Self {
+        SmallRangeSet::default()
+    }
+
+    /// Remove all ranges from the set.
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// The number of disjoint ranges in the set.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+impl<T, const N: usize> SmallRangeSet<T, N>
+where
+    T: Ord + Clone,
+{
+    /// Get an iterator over the disjoint ranges in the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = Range<T>> + Clone + '_ {
+        self.ranges
+            .iter()
+            .map(|kvp| kvp.key.clone()..kvp.value.clone())
+    }
+
+    /// Adds a range to the set, merging it with any overlapping or adjacent ranges.
+    ///
+    /// Empty ranges are ignored.
+    pub fn insert_range(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let lo = self.ranges.partition_point(|r| r.value < range.start);
+        let hi = self.ranges.partition_point(|r| r.key <= range.end);
+        let mut merged = KeyValuePair {
+            key: range.start,
+            value: range.end,
+        };
+        if lo < hi {
+            if self.ranges[lo].key < merged.key {
+                merged.key = self.ranges[lo].key.clone();
+            }
+            if self.ranges[hi - 1].value > merged.value {
+                merged.value = self.ranges[hi - 1].value.clone();
+            }
+            self.ranges.drain(lo..hi);
+        }
+        self.ranges.storage.insert(lo, merged);
+    }
+
+    /// Removes a range from the set, splitting any ranges which partially overlap it.
+    ///
+    /// Empty ranges are ignored.
+    pub fn remove_range(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let lo = self.ranges.partition_point(|r| r.value <= range.start);
+        let hi = self.ranges.partition_point(|r| r.key < range.end);
+        if lo == hi {
+            return;
+        }
+
+        let first = &self.ranges[lo];
+        let left = if first.key < range.start {
+            Some(KeyValuePair {
+                key: first.key.clone(),
+                value: range.start,
+            })
+        } else {
+            None
+        };
+        let last = &self.ranges[hi - 1];
+        let right = if last.value > range.end {
+            Some(KeyValuePair {
+                key: range.end,
+                value: last.value.clone(),
+            })
+        } else {
+            None
+        };
+
+        self.ranges.drain(lo..hi);
+        self.ranges
+            .storage
+            .insert_many(lo, left.into_iter().chain(right));
+    }
+
+    /// Returns `true` if the set contains the given point.
+    pub fn contains(&self, point: &T) -> bool {
+        match self.ranges.partition_point(|r| r.key <= *point) {
+            0 => false,
+            idx => *point < self.ranges[idx - 1].value,
+        }
+    }
+
+    /// Returns `true` if the given range is entirely contained in the set.
+    ///
+    /// Empty ranges are always covered.
+    pub fn covers(&self, range: Range<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+
+        match self.ranges.partition_point(|r| r.key <= range.start) {
+            0 => false,
+            idx => range.end <= self.ranges[idx - 1].value,
+        }
+    }
+
+    /// Get an iterator over the ranges within `within` which are not contained in the set, in
+    /// ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallRangeSet;
+    ///
+    /// let set: SmallRangeSet<u32, 4> = vec![2..4, 6..8].into_iter().collect();
+    ///
+    /// assert_eq!(set.gaps(0..10).collect::<Vec<_>>(), [0..2, 4..6, 8..10]);
+    /// assert_eq!(set.gaps(3..7).collect::<Vec<_>>(), [4..6]);
+    /// ```
+    pub fn gaps(&self, within: Range<T>) -> Gaps<'_, T> {
+        let lo = self.ranges.partition_point(|r| r.value <= within.start);
+        Gaps {
+            iter: self.ranges[lo..].iter(),
+            start: within.start,
+            end: within.end,
+        }
+    }
+
+    /// Returns the union of two range sets, containing every point in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = SmallRangeSet::new();
+        let mut lhs = self.ranges.iter().peekable();
+        let mut rhs = other.ranges.iter().peekable();
+        loop {
+            let next = match (lhs.peek(), rhs.peek()) {
+                (Some(l), Some(r)) if l.key <= r.key => lhs.next(),
+                (Some(_), Some(_)) => rhs.next(),
+                (Some(_), None) => lhs.next(),
+                (None, Some(_)) => rhs.next(),
+                (None, None) => break,
+            };
+            if let Some(next) = next {
+                result.push_coalesce(next.key.clone()..next.value.clone());
+            }
+        }
+        result
+    }
+
+    /// Returns the intersection of two range sets, containing every point in both sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallRangeSet;
+    ///
+    /// let a: SmallRangeSet<u32, 4> = vec![0..5, 10..15].into_iter().collect();
+    /// let b: SmallRangeSet<u32, 4> = vec![3..12].into_iter().collect();
+    ///
+    /// assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), [3..5, 10..12]);
+    /// assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), [0..15]);
+    /// assert_eq!(a.complement(0..20).iter().collect::<Vec<_>>(), [5..10, 15..20]);
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = SmallRangeSet::new();
+        let mut lhs = self.ranges.iter().peekable();
+        let mut rhs = other.ranges.iter().peekable();
+        while let (Some(l), Some(r)) = (lhs.peek(), rhs.peek()) {
+            let start = Ord::max(&l.key, &r.key);
+            let end = Ord::min(&l.value, &r.value);
+            if start < end {
+                result.ranges.storage.push(KeyValuePair {
+                    key: start.clone(),
+                    value: end.clone(),
+                });
+            }
+            if l.value <= r.value {
+                lhs.next();
+            } else {
+                rhs.next();
+            }
+        }
+        result
+    }
+
+    /// Returns the complement of the range set within `within`, containing every point in
+    /// `within` which is not in the set.
+    pub fn complement(&self, within: Range<T>) -> Self {
+        SmallRangeSet {
+            ranges: SmallOrdSet::from_vec_unchecked(
+                self.gaps(within)
+                    .map(|gap| KeyValuePair {
+                        key: gap.start,
+                        value: gap.end,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn push_coalesce(&mut self, range: Range<T>) {
+        if let Some(last) = self.ranges.storage.last_mut() {
+            if range.start <= last.value {
+                if range.end > last.value {
+                    last.value = range.end;
+                }
+                return;
+            }
+        }
+        self.ranges.storage.push(KeyValuePair {
+            key: range.start,
+            value: range.end,
+        });
+    }
+}
+
+impl<T> Iterator for Gaps<'_, T>
+where
+    T: Ord + Clone,
+{
+    type Item = Range<T>;
+
+    fn next(&mut self) -> Option<Range<T>> {
+        while self.start < self.end {
+            match self.iter.next() {
+                Some(r) if r.key <= self.start => {
+                    if r.value > self.start {
+                        self.start = r.value.clone();
+                    }
+                }
+                Some(r) => {
+                    let gap = self.start.clone()..Ord::min(&r.key, &self.end).clone();
+                    self.start = r.value.clone();
+                    return Some(gap);
+                }
+                None => {
+                    let gap = self.start.clone()..self.end.clone();
+                    self.start = self.end.clone();
+                    return Some(gap);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<T> FusedIterator for Gaps<'_, T> where T: Ord + Clone {}
+
+impl<T, const N: usize> Clone for SmallRangeSet<T, N>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallRangeSet {
+            ranges: self.ranges.clone(),
+        }
+    }
+}
+
+impl<T, const N: usize> Debug for SmallRangeSet<T, N>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries(self.ranges.iter().map(|kvp| &kvp.key..&kvp.value))
+            .finish()
+    }
+}
+
+impl<T: Debug> Debug for Gaps<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Gaps")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for SmallRangeSet<T, N> {
+    fn default() -> Self {
+        SmallRangeSet {
+            ranges: SmallOrdSet::default(),
+        }
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallRangeSet<T, N> {}
+
+impl<T, const N: usize> Extend<Range<T>> for SmallRangeSet<T, N>
+where
+    T: Ord + Clone,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Range<T>>,
+    {
+        for range in iter {
+            self.insert_range(range);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<Range<T>> for SmallRangeSet<T, N>
+where
+    T: Ord + Clone,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Range<T>>,
+    {
+        let mut set = SmallRangeSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for SmallRangeSet<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ranges.len().hash(state);
+        for kvp in &self.ranges {
+            kvp.key.hash(state);
+            kvp.value.hash(state);
+        }
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for SmallRangeSet<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ranges.len() == other.ranges.len()
+            && self
+                .ranges
+                .iter()
+                .zip(&other.ranges)
+                .all(|(l, r)| l.key == r.key && l.value == r.value)
+    }
+}
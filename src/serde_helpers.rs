@@ -0,0 +1,169 @@
+//! Alternative serde representations of [`SmallOrdSet`](../type.SmallOrdSet.html), for use with
+//! serde's `with` attribute.
+//!
+//! By default, a `SmallOrdSet` is serialized as a sequence, and deserialized from a sequence in
+//! any order, sorting it and removing duplicates like
+//! [`from_vec`](../struct.SortedSet.html#method.from_vec). The modules here instead serialize
+//! maps of [`KeyValuePair`](../struct.KeyValuePair.html)s as serde maps, or reject input which is
+//! not strictly sorted with an error giving the position of the first offending element.
+//!
+//! A [`SmallOrdMap`](../struct.SmallOrdMap.html) is serialized as a map by default.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use small_ord_set::{serde_helpers, KeyValuePair, SmallOrdSet};
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "serde_helpers::strict")]
+//!     tags: SmallOrdSet<[String; 4]>,
+//!     #[serde(with = "serde_helpers::map")]
+//!     limits: SmallOrdSet<[KeyValuePair<String, u32>; 4]>,
+//! }
+//!
+//! let config: Config =
+//!     serde_json::from_str(r#"{"tags":["a","b"],"limits":{"cpu":2,"mem":512}}"#).unwrap();
+//! assert_eq!(config.limits.get_value("mem"), Some(&512));
+//! assert_eq!(
+//!     serde_json::to_string(&config).unwrap(),
+//!     r#"{"tags":["a","b"],"limits":{"cpu":2,"mem":512}}"#
+//! );
+//!
+//! let err = serde_json::from_str::<Config>(r#"{"tags":["b","a"],"limits":{}}"#).unwrap_err();
+//! assert!(err.to_string().starts_with("element at index 1 is out of order"));
+//! ```
+
+/// Serialize a set as a sequence, and deserialize it from a sequence which must be in strictly
+/// ascending order.
+pub mod strict {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use smallvec::Array;
+
+    use crate::SmallOrdSet;
+
+    /// Serialize a set as a sequence.
+    pub fn serialize<A, S>(set: &SmallOrdSet<A>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        A: Array,
+        A::Item: Serialize,
+        S: Serializer,
+    {
+        set.serialize(serializer)
+    }
+
+    /// Deserialize a set from a sequence, returning an error if it is not in strictly ascending
+    /// order.
+    pub fn deserialize<'de, A, D>(deserializer: D) -> Result<SmallOrdSet<A>, D::Error>
+    where
+        A: Array,
+        A::Item: Deserialize<'de> + Ord,
+        D: Deserializer<'de>,
+    {
+        crate::serde_impl::deserialize_seq(deserializer, true)
+    }
+}
+
+/// Serialize a map of key-value pairs as a serde map, and deserialize it from a map in any order.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use small_ord_set::{serde_helpers, KeyValuePair, SmallOrdSet};
+///
+/// #[derive(Deserialize)]
+/// struct Limits {
+///     #[serde(with = "serde_helpers::map")]
+///     limits: SmallOrdSet<[KeyValuePair<String, u32>; 4]>,
+/// }
+///
+/// let config: Limits =
+///     serde_json::from_str(r#"{"limits":{"mem":1,"cpu":2,"mem":3}}"#).unwrap();
+/// assert_eq!(config.limits.keys().collect::<Vec<_>>(), ["cpu", "mem"]);
+/// assert_eq!(config.limits.get_value("mem"), Some(&3));
+/// ```
+pub mod map {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use smallvec::Array;
+
+    use crate::{KeyValuePair, SmallOrdSet};
+
+    /// Serialize a set of key-value pairs as a map.
+    pub fn serialize<A, K, V, S>(set: &SmallOrdSet<A>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        A: Array<Item = KeyValuePair<K, V>>,
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        crate::serde_impl::serialize_map(set, serializer)
+    }
+
+    /// Deserialize a set of key-value pairs from a map, sorting it by key. If a key appears more
+    /// than once, the last value for it is kept.
+    pub fn deserialize<'de, A, K, V, D>(deserializer: D) -> Result<SmallOrdSet<A>, D::Error>
+    where
+        A: Array<Item = KeyValuePair<K, V>>,
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        crate::serde_impl::deserialize_map(deserializer, false)
+    }
+}
+
+/// Serialize a map of key-value pairs as a serde map, and deserialize it from a map whose keys
+/// must be in strictly ascending order.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use small_ord_set::{serde_helpers, KeyValuePair, SmallOrdSet};
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Limits {
+///     #[serde(with = "serde_helpers::map_strict")]
+///     limits: SmallOrdSet<[KeyValuePair<String, u32>; 4]>,
+/// }
+///
+/// let config: Limits = serde_json::from_str(r#"{"limits":{"cpu":2,"mem":1}}"#).unwrap();
+/// assert_eq!(config.limits.get_value("mem"), Some(&1));
+///
+/// let err = serde_json::from_str::<Limits>(r#"{"limits":{"mem":1,"cpu":2}}"#).unwrap_err();
+/// assert!(err.to_string().starts_with("key at index 1 is out of order"));
+///
+/// let err = serde_json::from_str::<Limits>(r#"{"limits":{"cpu":1,"cpu":2}}"#).unwrap_err();
+/// assert!(err.to_string().starts_with("duplicate key at index 1"));
+/// ```
+pub mod map_strict {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use smallvec::Array;
+
+    use crate::{KeyValuePair, SmallOrdSet};
+
+    /// Serialize a set of key-value pairs as a map.
+    pub fn serialize<A, K, V, S>(set: &SmallOrdSet<A>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        A: Array<Item = KeyValuePair<K, V>>,
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        crate::serde_impl::serialize_map(set, serializer)
+    }
+
+    /// Deserialize a set of key-value pairs from a map, returning an error if its keys are not in
+    /// strictly ascending order.
+    pub fn deserialize<'de, A, K, V, D>(deserializer: D) -> Result<SmallOrdSet<A>, D::Error>
+    where
+        A: Array<Item = KeyValuePair<K, V>>,
+        K: Deserialize<'de> + Ord,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        crate::serde_impl::deserialize_map(deserializer, true)
+    }
+}
@@ -0,0 +1,247 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+use smallvec::{Array, SmallVec};
+
+use crate::{KeyValuePair, SmallOrdMap, SmallOrdSet};
+
+/// Serializes the set as a sequence of its elements.
+///
+/// Sets of [`KeyValuePair`](struct.KeyValuePair.html)s are also serialized as a sequence, of
+/// `[key, value]` tuples rather than as a map. Use [`SmallOrdMap`](struct.SmallOrdMap.html) or
+/// [`serde_helpers::map`](serde_helpers/map/index.html) to serialize them as a map.
+impl<A> Serialize for SmallOrdSet<A>
+where
+    A: Array,
+    A::Item: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, A> Deserialize<'de> for SmallOrdSet<A>
+where
+    A: Array,
+    A::Item: Deserialize<'de> + Ord,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_seq(deserializer, false)
+    }
+}
+
+/// Serializes the map as a map from keys to values.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{KeyValuePair, SmallOrdMap, SmallOrdSet};
+///
+/// let set = SmallOrdSet::from_buf([KeyValuePair { key: "a", value: 1 }]);
+/// assert_eq!(serde_json::to_string(&set).unwrap(), r#"[["a",1]]"#);
+///
+/// let map = SmallOrdMap::from_set(set);
+/// assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"a":1}"#);
+///
+/// let map: SmallOrdMap<String, u32, 2> = serde_json::from_str(r#"{"b":2,"a":1}"#).unwrap();
+/// assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "b"]);
+/// ```
+impl<K, V, const N: usize> Serialize for SmallOrdMap<K, V, N>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_map(self, serializer)
+    }
+}
+
+/// Deserializes the map from a map in any order, sorting it by key.
+///
+/// Like `BTreeMap`, if a key appears more than once, the last value for it is kept.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::SmallOrdMap;
+///
+/// let map: SmallOrdMap<String, u32, 4> =
+///     serde_json::from_str(r#"{"a":1,"b":5,"a":2,"a":3}"#).unwrap();
+/// assert_eq!(map.get_value("a"), Some(&3));
+/// assert_eq!(map.get_value("b"), Some(&5));
+/// assert_eq!(map.len(), 2);
+/// ```
+impl<'de, K, V, const N: usize> Deserialize<'de> for SmallOrdMap<K, V, N>
+where
+    K: Deserialize<'de> + Ord,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_map(deserializer, false).map(SmallOrdMap::from_set)
+    }
+}
+
+impl<K, V> Serialize for KeyValuePair<K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.key)?;
+        tuple.serialize_element(&self.value)?;
+        tuple.end()
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for KeyValuePair<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (key, value) = Deserialize::deserialize(deserializer)?;
+        Ok(KeyValuePair { key, value })
+    }
+}
+
+pub(crate) fn serialize_map<A, K, V, S>(
+    set: &SmallOrdSet<A>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    A: Array<Item = KeyValuePair<K, V>>,
+    K: Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    serializer.collect_map(set.iter().map(|kvp| (&kvp.key, &kvp.value)))
+}
+
+pub(crate) fn deserialize_seq<'de, A, D>(
+    deserializer: D,
+    strict: bool,
+) -> Result<SmallOrdSet<A>, D::Error>
+where
+    A: Array,
+    A::Item: Deserialize<'de> + Ord,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_seq(SeqVisitor {
+        strict,
+        marker: PhantomData,
+    })
+}
+
+pub(crate) fn deserialize_map<'de, A, K, V, D>(
+    deserializer: D,
+    strict: bool,
+) -> Result<SmallOrdSet<A>, D::Error>
+where
+    A: Array<Item = KeyValuePair<K, V>>,
+    K: Deserialize<'de> + Ord,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(MapVisitor {
+        strict,
+        marker: PhantomData,
+    })
+}
+
+struct SeqVisitor<A> {
+    strict: bool,
+    marker: PhantomData<A>,
+}
+
+struct MapVisitor<A> {
+    strict: bool,
+    marker: PhantomData<A>,
+}
+
+impl<'de, A> Visitor<'de> for SeqVisitor<A>
+where
+    A: Array,
+    A::Item: Deserialize<'de> + Ord,
+{
+    type Value = SmallOrdSet<A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.strict {
+            f.write_str("a sequence in strictly ascending order")
+        } else {
+            f.write_str("a sequence")
+        }
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let mut vec = SmallVec::<A>::new();
+        while let Some(element) = seq.next_element()? {
+            if self.strict {
+                check_order(vec.last(), &element, vec.len(), "element")?;
+            }
+            vec.push(element);
+        }
+        if self.strict {
+            Ok(SmallOrdSet::from_vec_unchecked(vec))
+        } else {
+            Ok(SmallOrdSet::from_vec(vec))
+        }
+    }
+}
+
+impl<'de, A, K, V> Visitor<'de> for MapVisitor<A>
+where
+    A: Array<Item = KeyValuePair<K, V>>,
+    K: Deserialize<'de> + Ord,
+    V: Deserialize<'de>,
+{
+    type Value = SmallOrdSet<A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.strict {
+            f.write_str("a map with keys in strictly ascending order")
+        } else {
+            f.write_str("a map")
+        }
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+        let mut vec = SmallVec::<A>::new();
+        while let Some((key, value)) = map.next_entry()? {
+            if self.strict {
+                check_order(vec.last().map(|kvp| &kvp.key), &key, vec.len(), "key")?;
+            }
+            vec.push(KeyValuePair { key, value });
+        }
+        if !self.strict {
+            // Keep the last value for each key, like inserting into a `BTreeMap`. Reversing first
+            // makes it the first of its run after the stable sort.
+            vec.reverse();
+            vec.sort_by(|a, b| Ord::cmp(&a.key, &b.key));
+            vec.dedup_by(|a, b| a.key == b.key);
+        }
+        Ok(SmallOrdSet::from_vec_unchecked(vec))
+    }
+}
+
+fn check_order<T, E>(prev: Option<&T>, next: &T, index: usize, what: &str) -> Result<(), E>
+where
+    T: Ord,
+    E: de::Error,
+{
+    match prev.map(|prev| Ord::cmp(prev, next)) {
+        Some(Ordering::Equal) => Err(E::custom(format_args!(
+            "duplicate {} at index {}",
+            what, index
+        ))),
+        Some(Ordering::Greater) => Err(E::custom(format_args!(
+            "{} at index {} is out of order",
+            what, index
+        ))),
+        _ => Ok(()),
+    }
+}
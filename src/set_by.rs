@@ -0,0 +1,382 @@
+use core::borrow::Borrow;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{Deref, RangeBounds};
+use core::slice;
+
+use smallvec::{self, Array, SmallVec};
+
+use crate::{Comparator, SmallOrdSet};
+
+/// A set represented by a `SmallVec` sorted by a custom [`Comparator`](trait.Comparator.html).
+///
+/// This behaves like [`SmallOrdSet`](type.SmallOrdSet.html), but does not require its elements
+/// to implement `Ord`.
+///
+/// Methods which do not depend on the order of the set, such as
+/// [`find_by`](struct.SortedSet.html#method.find_by) and
+/// [`range_by`](struct.SortedSet.html#method.range_by), are available through
+/// [`as_set`](#method.as_set), and slice methods are available through `Deref`.
+///
+/// Like `SmallOrdSet`, two sets are equal if their elements are pairwise equal according to
+/// `PartialEq`. The comparators are not used, so equality is symmetric even if the comparators
+/// are stateful.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{SmallOrdSetBy, CaseInsensitive, Reverse};
+///
+/// let mut set = SmallOrdSetBy::<[&str; 4], CaseInsensitive>::new();
+/// set.insert("Hello");
+/// set.insert("world");
+/// assert_eq!(set.insert("HELLO"), false);
+/// assert_eq!(set.as_slice(), &["Hello", "world"]);
+///
+/// let set = SmallOrdSetBy::from_buf([1, 3, 2], Reverse);
+/// assert_eq!(set.as_slice(), &[3, 2, 1]);
+///
+/// assert_ne!(
+///     SmallOrdSetBy::from_buf(["Hello"], CaseInsensitive),
+///     SmallOrdSetBy::from_buf(["hello"], CaseInsensitive),
+/// );
+/// ```
+pub struct SmallOrdSetBy<A: Array, C> {
+    set: SmallOrdSet<A>,
+    cmp: C,
+}
+
+impl<A: Array, C> SmallOrdSetBy<A, C> {
+    /// Make a new, empty, `SmallOrdSetBy`.
+    pub fn new() -> Self
+    where
+        C: Default,
+    {
+        SmallOrdSetBy::default()
+    }
+
+    /// Make a new, empty, `SmallOrdSetBy` using the given comparator.
+    pub fn with_comparator(cmp: C) -> Self {
+        SmallOrdSetBy::from_vec_unchecked(SmallVec::new(), cmp)
+    }
+
+    /// Construct a new [`SmallOrdSetBy`](struct.SmallOrdSetBy.html) from a `SmallVec` sorted by
+    /// `cmp`. `vec` must be sorted and may not contain duplicate elements.
+    ///
+    /// # Safety
+    ///
+    /// Failure to uphold the restrictions on the `vec` parameter will not cause memory unsafety,
+    /// however the result of any operations on the resulting `SmallOrdSetBy` is unspecified.
+    pub fn from_vec_unchecked(vec: SmallVec<A>, cmp: C) -> Self {
+        SmallOrdSetBy {
+            set: SmallOrdSet::from_vec_unchecked(vec),
+            cmp,
+        }
+    }
+
+    /// Get a reference to the comparator used to order the set.
+    pub fn comparator(&self) -> &C {
+        &self.cmp
+    }
+
+    /// Get a reference to the underlying set.
+    ///
+    /// Lookups on the returned set, such as `contains` and `lower_bound`, use the `Ord`
+    /// implementation of the elements instead of the comparator, so only methods which do not
+    /// depend on the order should be used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::{SmallOrdSetBy, Reverse};
+    ///
+    /// let set = SmallOrdSetBy::from_buf([1, 3, 2, 5], Reverse);
+    ///
+    /// assert_eq!(set.as_set().find_by(|probe| 3.cmp(probe)), Ok(1));
+    /// assert_eq!(set.as_set().partition_point(|&probe| probe > 2), 2);
+    /// assert_eq!(set.as_set().inline_size(), 4);
+    /// ```
+    pub fn as_set(&self) -> &SmallOrdSet<A> {
+        &self.set
+    }
+
+    /// Get a slice containing the whole set in sorted order.
+    pub fn as_slice(&self) -> &[A::Item] {
+        self.set.as_slice()
+    }
+
+    /// Remove all elements from the set.
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    /// Creates a draining iterator that removes the specified range in the set
+    /// and yields the removed items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the set.
+    pub fn drain<R>(&mut self, range: R) -> smallvec::Drain<'_, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.set.drain(range)
+    }
+
+    /// Convert the set into the inner `SmallVec`.
+    pub fn into_vec(self) -> SmallVec<A> {
+        self.set.into_vec()
+    }
+
+    /// Reserve capacity for `additional` more elements to be inserted.
+    ///
+    /// May reserve more space to avoid frequent reallocations.
+    pub fn reserve(&mut self, additional: usize) {
+        self.set.reserve(additional)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut A::Item) -> bool,
+    {
+        self.set.retain(f)
+    }
+}
+
+impl<A, C> SmallOrdSetBy<A, C>
+where
+    A: Array,
+    C: Comparator<A::Item>,
+{
+    /// Construct a new [`SmallOrdSetBy`](struct.SmallOrdSetBy.html) from a `SmallVec`. The vector
+    /// will be sorted by `cmp` and duplicate elements removed.
+    pub fn from_vec(vec: SmallVec<A>, cmp: C) -> Self {
+        let mut set = SmallOrdSetBy::from_vec_unchecked(vec, cmp);
+        set.sort_and_dedup();
+        set
+    }
+
+    /// Constructs a new [`SmallOrdSetBy`](struct.SmallOrdSetBy.html) on the stack from an `A`
+    /// without copying elements.
+    pub fn from_buf(buf: A, cmp: C) -> Self {
+        SmallOrdSetBy::from_vec(buf.into(), cmp)
+    }
+
+    /// Moves all elements from `other` into `Self`, leaving other `empty`.
+    pub fn append(&mut self, other: &mut Self) {
+        self.extend(other.drain(..))
+    }
+
+    /// Adds an element to the set.
+    ///
+    /// If the set did not have this element present, `true` is returned.
+    ///
+    /// If the set did have this element present, `false` is returned, and the
+    /// entry is not updated.
+    pub fn insert(&mut self, element: A::Item) -> bool {
+        let cmp = &self.cmp;
+        self.set.insert_by(element, |a, b| cmp.compare(a, b))
+    }
+
+    /// Adds a element to the set, replacing the existing element, if any, that is equal to the
+    /// given one. Returns the replaced element.
+    pub fn replace(&mut self, element: A::Item) -> Option<A::Item> {
+        let cmp = &self.cmp;
+        self.set.replace_by(element, |a, b| cmp.compare(a, b))
+    }
+
+    /// Removes and returns the element in the set, if any, that is equal to the given one.
+    ///
+    /// The element may be any borrowed form of the set's element type, but the comparator's
+    /// ordering on the borrowed form *must* match its ordering on the element type.
+    pub fn remove<Q>(&mut self, element: &Q) -> Option<A::Item>
+    where
+        A::Item: Borrow<Q>,
+        C: Comparator<Q>,
+        Q: ?Sized,
+    {
+        let cmp = &self.cmp;
+        self.set
+            .remove_by(|probe| cmp.compare(probe.borrow(), element))
+    }
+
+    /// Returns `true` if the set contains an element.
+    ///
+    /// The value may be any borrowed form of the set's element type, but the comparator's
+    /// ordering on the borrowed form *must* match its ordering on the element type.
+    pub fn contains<Q>(&self, element: &Q) -> bool
+    where
+        A::Item: Borrow<Q>,
+        C: Comparator<Q>,
+        Q: ?Sized,
+    {
+        self.find(element).is_ok()
+    }
+
+    /// Returns a reference to the element in the set, if any, that is equal to the given value.
+    ///
+    /// The value may be any borrowed form of the set's element type, but the comparator's
+    /// ordering on the borrowed form *must* match its ordering on the element type.
+    pub fn get<Q>(&self, element: &Q) -> Option<&A::Item>
+    where
+        A::Item: Borrow<Q>,
+        C: Comparator<Q>,
+        Q: ?Sized,
+    {
+        match self.find(element) {
+            Ok(idx) => Some(&self.set.storage[idx]),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the element in the set, if any, that is equal to the given
+    /// value. It is an error to mutate the element such that its ordering changes.
+    ///
+    /// The value may be any borrowed form of the set's element type, but the comparator's
+    /// ordering on the borrowed form *must* match its ordering on the element type.
+    pub fn get_mut<Q>(&mut self, element: &Q) -> Option<&mut A::Item>
+    where
+        A::Item: Borrow<Q>,
+        C: Comparator<Q>,
+        Q: ?Sized,
+    {
+        match self.find(element) {
+            Ok(idx) => Some(&mut self.set.storage[idx]),
+            Err(_) => None,
+        }
+    }
+
+    fn find<Q>(&self, element: &Q) -> Result<usize, usize>
+    where
+        A::Item: Borrow<Q>,
+        C: Comparator<Q>,
+        Q: ?Sized,
+    {
+        self.set
+            .find_by(|probe| self.cmp.compare(probe.borrow(), element))
+    }
+
+    fn sort_and_dedup(&mut self) {
+        let cmp = &self.cmp;
+        self.set.sort_and_dedup_by(|a, b| cmp.compare(a, b))
+    }
+}
+
+impl<A: Array, C> AsRef<[A::Item]> for SmallOrdSetBy<A, C> {
+    fn as_ref(&self) -> &[A::Item] {
+        self.as_slice()
+    }
+}
+
+impl<A, C> Clone for SmallOrdSetBy<A, C>
+where
+    A: Array,
+    A::Item: Clone,
+    C: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallOrdSetBy {
+            set: self.set.clone(),
+            cmp: self.cmp.clone(),
+        }
+    }
+}
+
+impl<A, C> Debug for SmallOrdSetBy<A, C>
+where
+    A: Array,
+    A::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<A: Array, C: Default> Default for SmallOrdSetBy<A, C> {
+    fn default() -> Self {
+        SmallOrdSetBy::with_comparator(C::default())
+    }
+}
+
+impl<A: Array, C> Deref for SmallOrdSetBy<A, C> {
+    type Target = [A::Item];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<A, C> Eq for SmallOrdSetBy<A, C>
+where
+    A: Array,
+    A::Item: Eq,
+{
+}
+
+impl<A, C> Extend<A::Item> for SmallOrdSetBy<A, C>
+where
+    A: Array,
+    C: Comparator<A::Item>,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = A::Item>,
+    {
+        self.set.storage.extend(iter);
+        self.sort_and_dedup();
+    }
+}
+
+impl<A, C> FromIterator<A::Item> for SmallOrdSetBy<A, C>
+where
+    A: Array,
+    C: Comparator<A::Item> + Default,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = A::Item>,
+    {
+        SmallOrdSetBy::from_vec(FromIterator::from_iter(iter), C::default())
+    }
+}
+
+impl<A, C> Hash for SmallOrdSetBy<A, C>
+where
+    A: Array,
+    A::Item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.set.hash(state)
+    }
+}
+
+impl<A: Array, C> IntoIterator for SmallOrdSetBy<A, C> {
+    type IntoIter = smallvec::IntoIter<A>;
+    type Item = A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.into_iter()
+    }
+}
+
+impl<'a, A: Array, C> IntoIterator for &'a SmallOrdSetBy<A, C> {
+    type IntoIter = slice::Iter<'a, A::Item>;
+    type Item = &'a A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<A, C> PartialEq for SmallOrdSetBy<A, C>
+where
+    A: Array,
+    A::Item: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&self.set, &other.set)
+    }
+}
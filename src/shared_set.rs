@@ -0,0 +1,293 @@
+use alloc::sync::Arc;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::mem::replace;
+use core::ops::{Deref, DerefMut};
+use core::slice;
+
+use smallvec::Array;
+
+use crate::{Comparable, SmallOrdSet};
+
+/// A [`SmallOrdSet`] with copy-on-write structural sharing.
+///
+/// While the set fits inline it is stored directly, like a `SmallOrdSet`. Once it spills to the
+/// heap, it is moved behind an `Arc`, so that cloning it is `O(1)`. Mutation goes through
+/// [`make_mut`], which copies the elements only if they are shared with another clone.
+///
+/// All of the read-only methods of `SmallOrdSet` are available through `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{SharedOrdSet, SmallOrdSet};
+///
+/// let tags: SharedOrdSet<[&str; 2]> = ["web", "db", "cache"].iter().copied().collect();
+///
+/// let mut worker_tags = tags.clone();
+/// assert!(worker_tags.ptr_eq(&tags));
+///
+/// worker_tags.make_mut().insert("queue");
+/// assert!(!worker_tags.ptr_eq(&tags));
+/// assert!(worker_tags.contains("queue"));
+/// assert!(!tags.contains("queue"));
+/// ```
+///
+/// [`SmallOrdSet`]: type.SmallOrdSet.html
+/// [`make_mut`]: #method.make_mut
+pub struct SharedOrdSet<A: Array> {
+    repr: Repr<A>,
+}
+
+/// A guard providing mutable access to the set inside a [`SharedOrdSet`].
+///
+/// When the guard is dropped, the set is moved behind an `Arc` if it has spilled to the heap, or
+/// back inline if it no longer needs to be shared.
+///
+/// This `struct` is constructed from the [`make_mut`] method on [`SharedOrdSet`].
+///
+/// [`SharedOrdSet`]: struct.SharedOrdSet.html
+/// [`make_mut`]: struct.SharedOrdSet.html#method.make_mut
+pub struct SharedOrdSetMut<'a, A: Array> {
+    shared: &'a mut SharedOrdSet<A>,
+}
+
+enum Repr<A: Array> {
+    Inline(SmallOrdSet<A>),
+    Shared(Arc<SmallOrdSet<A>>),
+}
+
+impl<A: Array> SharedOrdSet<A> {
+    /// Make a new, empty, `SharedOrdSet`.
+    pub fn new() -> Self {
+        SharedOrdSet::default()
+    }
+
+    /// Get a reference to the underlying set.
+    pub fn as_set(&self) -> &SmallOrdSet<A> {
+        match &self.repr {
+            Repr::Inline(set) => set,
+            Repr::Shared(set) => set,
+        }
+    }
+
+    /// Returns `true` if both sets share the same heap storage.
+    ///
+    /// Sets which are stored inline never share storage.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.repr, &other.repr) {
+            (Repr::Shared(lhs), Repr::Shared(rhs)) => Arc::ptr_eq(lhs, rhs),
+            _ => false,
+        }
+    }
+
+    fn normalize(&mut self) {
+        let spilled = self.as_set().storage.spilled();
+        self.repr = match replace(&mut self.repr, Repr::Inline(SmallOrdSet::new())) {
+            Repr::Inline(set) if spilled => Repr::Shared(Arc::new(set)),
+            Repr::Shared(set) if !spilled => match Arc::try_unwrap(set) {
+                Ok(set) => Repr::Inline(set),
+                Err(set) => Repr::Shared(set),
+            },
+            repr => repr,
+        };
+    }
+}
+
+impl<A> SharedOrdSet<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    /// Get mutable access to the set, copying its elements first if they are shared with another
+    /// `SharedOrdSet`.
+    pub fn make_mut(&mut self) -> SharedOrdSetMut<'_, A> {
+        if let Repr::Shared(set) = &mut self.repr {
+            Arc::make_mut(set);
+        }
+        SharedOrdSetMut { shared: self }
+    }
+
+    /// Convert into a `SmallOrdSet`, copying its elements if they are shared with another
+    /// `SharedOrdSet`.
+    pub fn into_set(self) -> SmallOrdSet<A> {
+        match self.repr {
+            Repr::Inline(set) => set,
+            Repr::Shared(set) => Arc::try_unwrap(set).unwrap_or_else(|set| (*set).clone()),
+        }
+    }
+}
+
+impl<A> SharedOrdSet<A>
+where
+    A: Array,
+    A::Item: Clone + Ord,
+{
+    /// Adds an element to the set, returning `true` if it was not already present.
+    ///
+    /// This function is a convenience wrapper around [`make_mut`](#method.make_mut), which does
+    /// not copy a shared set if the element is already present.
+    pub fn insert(&mut self, element: A::Item) -> bool {
+        if self.contains(&element) {
+            return false;
+        }
+        self.make_mut().insert(element)
+    }
+
+    /// Removes and returns the element in the set, if any, that is equal to the given one.
+    ///
+    /// This function is a convenience wrapper around [`make_mut`](#method.make_mut), which does
+    /// not copy a shared set if the element is not present.
+    pub fn remove<Q>(&mut self, element: &Q) -> Option<A::Item>
+    where
+        Q: Comparable<A::Item> + ?Sized,
+    {
+        if !self.contains(element) {
+            return None;
+        }
+        self.make_mut().remove(element)
+    }
+}
+
+impl<A> Clone for SharedOrdSet<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        let repr = match &self.repr {
+            Repr::Inline(set) => Repr::Inline(set.clone()),
+            Repr::Shared(set) => Repr::Shared(Arc::clone(set)),
+        };
+        SharedOrdSet { repr }
+    }
+}
+
+impl<A> Debug for SharedOrdSet<A>
+where
+    A: Array,
+    A::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self.as_set(), f)
+    }
+}
+
+impl<A> Debug for SharedOrdSetMut<'_, A>
+where
+    A: Array,
+    A::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self.shared.as_set(), f)
+    }
+}
+
+impl<A: Array> Default for SharedOrdSet<A> {
+    fn default() -> Self {
+        SharedOrdSet::from(SmallOrdSet::default())
+    }
+}
+
+impl<A: Array> Deref for SharedOrdSet<A> {
+    type Target = SmallOrdSet<A>;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_set()
+    }
+}
+
+impl<A: Array> Deref for SharedOrdSetMut<'_, A> {
+    type Target = SmallOrdSet<A>;
+
+    fn deref(&self) -> &Self::Target {
+        self.shared.as_set()
+    }
+}
+
+impl<A: Array> DerefMut for SharedOrdSetMut<'_, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.shared.repr {
+            Repr::Inline(set) => set,
+            Repr::Shared(set) => {
+                Arc::get_mut(set).expect("`make_mut` ensures the set is not shared")
+            }
+        }
+    }
+}
+
+impl<A: Array> Drop for SharedOrdSetMut<'_, A> {
+    fn drop(&mut self) {
+        self.shared.normalize();
+    }
+}
+
+impl<A> Eq for SharedOrdSet<A>
+where
+    A: Array,
+    A::Item: Eq,
+{
+}
+
+impl<A: Array> From<SmallOrdSet<A>> for SharedOrdSet<A> {
+    fn from(set: SmallOrdSet<A>) -> Self {
+        let mut shared = SharedOrdSet {
+            repr: Repr::Inline(set),
+        };
+        shared.normalize();
+        shared
+    }
+}
+
+impl<A> From<SharedOrdSet<A>> for SmallOrdSet<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    fn from(shared: SharedOrdSet<A>) -> Self {
+        shared.into_set()
+    }
+}
+
+impl<A> FromIterator<A::Item> for SharedOrdSet<A>
+where
+    A: Array,
+    A::Item: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = A::Item>,
+    {
+        SharedOrdSet::from(SmallOrdSet::from_iter(iter))
+    }
+}
+
+impl<A> Hash for SharedOrdSet<A>
+where
+    A: Array,
+    A::Item: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_set().hash(state)
+    }
+}
+
+impl<'a, A: Array> IntoIterator for &'a SharedOrdSet<A> {
+    type IntoIter = slice::Iter<'a, A::Item>;
+    type Item = &'a A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<A> PartialEq for SharedOrdSet<A>
+where
+    A: Array,
+    A::Item: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || PartialEq::eq(self.as_set(), other.as_set())
+    }
+}
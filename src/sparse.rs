@@ -0,0 +1,219 @@
+use core::cmp::Ordering;
+use core::ops::{Add, Mul, Sub};
+
+use smallvec::Array;
+
+use crate::{KeyValuePair, SmallOrdSet};
+
+/// A floating-point scalar type which can be used as the value of a sparse vector.
+///
+/// This trait is implemented for `f32` and `f64`.
+pub trait Scalar:
+    Copy + PartialEq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// The additive identity.
+    const ZERO: Self;
+
+    /// Returns the absolute value.
+    fn abs(self) -> Self;
+
+    /// Returns the square root.
+    fn sqrt(self) -> Self;
+
+    /// Divides `self` by `rhs`.
+    fn div(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0.0;
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            fn div(self, rhs: Self) -> Self {
+                self / rhs
+            }
+        }
+    )*};
+}
+
+impl_scalar!(f32, f64);
+
+/// Sparse vector arithmetic for maps from indices to [`Scalar`](trait.Scalar.html) values.
+///
+/// Missing keys are treated as zero. All operations are linear merges over the sorted keys of
+/// both vectors, and entries which are exactly zero are dropped from the results.
+///
+/// This trait is implemented for `SmallOrdSet`s of [`KeyValuePair`](struct.KeyValuePair.html)s,
+/// and must be imported to use its methods.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{KeyValuePair, SmallOrdSet, SparseVectorExt};
+///
+/// let x: SmallOrdSet<[KeyValuePair<u32, f32>; 4]> = vec![
+///     KeyValuePair { key: 1, value: 1.0 },
+///     KeyValuePair { key: 3, value: 2.0 },
+/// ].into_iter().collect();
+/// let y: SmallOrdSet<[KeyValuePair<u32, f32>; 4]> = vec![
+///     KeyValuePair { key: 3, value: 2.0 },
+///     KeyValuePair { key: 5, value: 4.0 },
+/// ].into_iter().collect();
+///
+/// assert_eq!(x.dot(&y), 4.0);
+/// assert_eq!(x.sub(&x).len(), 0);
+/// assert_eq!(x.add(&y).get_value(&3), Some(&4.0));
+/// assert_eq!(y.norm_l2(), 20f32.sqrt());
+/// ```
+pub trait SparseVectorExt: Sized {
+    /// The type of the entries of the sparse vector.
+    type Value: Scalar;
+
+    /// Returns the dot product of two sparse vectors.
+    fn dot(&self, other: &Self) -> Self::Value;
+
+    /// Returns the sum of two sparse vectors.
+    fn add(&self, other: &Self) -> Self;
+
+    /// Returns the difference of two sparse vectors.
+    fn sub(&self, other: &Self) -> Self;
+
+    /// Multiplies every entry of the sparse vector by `factor`.
+    fn scale(&mut self, factor: Self::Value);
+
+    /// Adds `alpha * x` to the sparse vector.
+    fn axpy(&mut self, alpha: Self::Value, x: &Self);
+
+    /// Returns the sum of the absolute values of the entries of the sparse vector.
+    fn norm_l1(&self) -> Self::Value;
+
+    /// Returns the Euclidean length of the sparse vector.
+    fn norm_l2(&self) -> Self::Value;
+
+    /// Returns the cosine of the angle between two sparse vectors.
+    ///
+    /// If either vector has no non-zero entries, zero is returned.
+    fn cosine_similarity(&self, other: &Self) -> Self::Value {
+        let norm = self.norm_l2() * other.norm_l2();
+        if norm == Self::Value::ZERO {
+            Self::Value::ZERO
+        } else {
+            self.dot(other).div(norm)
+        }
+    }
+}
+
+impl<A, K, V> SparseVectorExt for SmallOrdSet<A>
+where
+    A: Array<Item = KeyValuePair<K, V>>,
+    K: Ord + Clone,
+    V: Scalar,
+{
+    type Value = V;
+
+    fn dot(&self, other: &Self) -> V {
+        let mut sum = V::ZERO;
+        merge_keys(self, other, |_, lhs, rhs| {
+            if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                sum = sum + lhs * rhs;
+            }
+        });
+        sum
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        zip_with(self, other, |lhs, rhs| lhs + rhs)
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        zip_with(self, other, |lhs, rhs| lhs - rhs)
+    }
+
+    fn scale(&mut self, factor: V) {
+        self.retain(|kvp| {
+            kvp.value = kvp.value * factor;
+            kvp.value != V::ZERO
+        });
+    }
+
+    fn axpy(&mut self, alpha: V, x: &Self) {
+        *self = zip_with(self, x, |lhs, rhs| lhs + alpha * rhs);
+    }
+
+    fn norm_l1(&self) -> V {
+        self.iter().fold(V::ZERO, |sum, kvp| sum + kvp.value.abs())
+    }
+
+    fn norm_l2(&self) -> V {
+        self.iter()
+            .fold(V::ZERO, |sum, kvp| sum + kvp.value * kvp.value)
+            .sqrt()
+    }
+}
+
+fn zip_with<A, K, V, F>(lhs: &SmallOrdSet<A>, rhs: &SmallOrdSet<A>, mut f: F) -> SmallOrdSet<A>
+where
+    A: Array<Item = KeyValuePair<K, V>>,
+    K: Ord + Clone,
+    V: Scalar,
+    F: FnMut(V, V) -> V,
+{
+    let mut result = SmallOrdSet::new();
+    merge_keys(lhs, rhs, |key, lhs, rhs| {
+        let value = f(lhs.unwrap_or(V::ZERO), rhs.unwrap_or(V::ZERO));
+        if value != V::ZERO {
+            result.storage.push(KeyValuePair {
+                key: key.clone(),
+                value,
+            });
+        }
+    });
+    result
+}
+
+fn merge_keys<A, K, V, F>(lhs: &SmallOrdSet<A>, rhs: &SmallOrdSet<A>, mut f: F)
+where
+    A: Array<Item = KeyValuePair<K, V>>,
+    K: Ord,
+    V: Copy,
+    F: FnMut(&K, Option<V>, Option<V>),
+{
+    let mut lhs = lhs.iter().peekable();
+    let mut rhs = rhs.iter().peekable();
+    loop {
+        match (lhs.peek().copied(), rhs.peek().copied()) {
+            (Some(l), Some(r)) => match Ord::cmp(&l.key, &r.key) {
+                Ordering::Less => {
+                    f(&l.key, Some(l.value), None);
+                    lhs.next();
+                }
+                Ordering::Equal => {
+                    f(&l.key, Some(l.value), Some(r.value));
+                    lhs.next();
+                    rhs.next();
+                }
+                Ordering::Greater => {
+                    f(&r.key, None, Some(r.value));
+                    rhs.next();
+                }
+            },
+            (Some(l), None) => {
+                f(&l.key, Some(l.value), None);
+                lhs.next();
+            }
+            (None, Some(r)) => {
+                f(&r.key, None, Some(r.value));
+                rhs.next();
+            }
+            (None, None) => break,
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use alloc::collections::TryReserveError;
+use alloc::vec::Vec;
+
+use smallvec::{Array, CollectionAllocErr, SmallVec};
+
+#[cfg(any(feature = "arrayvec", feature = "heapless"))]
+use crate::CapacityError;
+
+/// A contiguous container which can be used as the backing storage of a
+/// [`SortedSet`](struct.SortedSet.html).
+///
+/// This trait is implemented for `SmallVec` and `Vec`, and for `arrayvec::ArrayVec`,
+/// `heapless::Vec` and `tinyvec::TinyVec` when the corresponding features are enabled. Containers
+/// with a bounded capacity report a full container by returning a
+/// [`CapacityError`](struct.CapacityError.html) from [`try_insert`](#tymethod.try_insert), while
+/// growable containers report allocation failures.
+pub trait SortedStorage {
+    /// The type of elements stored in the container.
+    type Item;
+
+    /// The error returned when an element cannot be inserted into the container.
+    type Error;
+
+    /// Get a slice containing all elements of the container.
+    fn as_slice(&self) -> &[Self::Item];
+
+    /// Get a mutable slice containing all elements of the container.
+    fn as_mut_slice(&mut self) -> &mut [Self::Item];
+
+    /// Insert an element at position `index`, shifting all elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container is full.
+    fn insert(&mut self, index: usize, element: Self::Item);
+
+    /// Insert an element at position `index`, shifting all elements after it to the right.
+    ///
+    /// Returns an error, leaving the container unchanged, if the container is full or space for
+    /// the element cannot be allocated.
+    fn try_insert(&mut self, index: usize, element: Self::Item) -> Result<(), Self::Error>;
+
+    /// Remove and return the element at position `index`, shifting all elements after it to the
+    /// left.
+    fn remove(&mut self, index: usize) -> Self::Item;
+
+    /// Shorten the container, dropping all elements after the first `len`.
+    fn truncate(&mut self, len: usize);
+}
+
+impl<A: Array> SortedStorage for SmallVec<A> {
+    type Item = A::Item;
+    type Error = CollectionAllocErr;
+
+    fn as_slice(&self) -> &[Self::Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+        self
+    }
+
+    fn insert(&mut self, index: usize, element: Self::Item) {
+        SmallVec::insert(self, index, element)
+    }
+
+    fn try_insert(&mut self, index: usize, element: Self::Item) -> Result<(), Self::Error> {
+        self.try_reserve(1)?;
+        SmallVec::insert(self, index, element);
+        Ok(())
+    }
+
+    fn remove(&mut self, index: usize) -> Self::Item {
+        SmallVec::remove(self, index)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        SmallVec::truncate(self, len)
+    }
+}
+
+impl<T> SortedStorage for Vec<T> {
+    type Item = T;
+    type Error = TryReserveError;
+
+    fn as_slice(&self) -> &[Self::Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+        self
+    }
+
+    fn insert(&mut self, index: usize, element: Self::Item) {
+        Vec::insert(self, index, element)
+    }
+
+    fn try_insert(&mut self, index: usize, element: Self::Item) -> Result<(), Self::Error> {
+        self.try_reserve(1)?;
+        Vec::insert(self, index, element);
+        Ok(())
+    }
+
+    fn remove(&mut self, index: usize) -> Self::Item {
+        Vec::remove(self, index)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        Vec::truncate(self, len)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<T, const CAP: usize> SortedStorage for arrayvec::ArrayVec<T, CAP> {
+    type Item = T;
+    type Error = CapacityError<T>;
+
+    fn as_slice(&self) -> &[Self::Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+        self
+    }
+
+    fn insert(&mut self, index: usize, element: Self::Item) {
+        if arrayvec::ArrayVec::try_insert(self, index, element).is_err() {
+            panic!("insufficient capacity")
+        }
+    }
+
+    fn try_insert(&mut self, index: usize, element: Self::Item) -> Result<(), Self::Error> {
+        arrayvec::ArrayVec::try_insert(self, index, element)
+            .map_err(|err| CapacityError::new(err.element()))
+    }
+
+    fn remove(&mut self, index: usize) -> Self::Item {
+        arrayvec::ArrayVec::remove(self, index)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        arrayvec::ArrayVec::truncate(self, len)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<T, const N: usize> SortedStorage for heapless::Vec<T, N> {
+    type Item = T;
+    type Error = CapacityError<T>;
+
+    fn as_slice(&self) -> &[Self::Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+        self
+    }
+
+    fn insert(&mut self, index: usize, element: Self::Item) {
+        if heapless::Vec::insert(self, index, element).is_err() {
+            panic!("insufficient capacity")
+        }
+    }
+
+    fn try_insert(&mut self, index: usize, element: Self::Item) -> Result<(), Self::Error> {
+        heapless::Vec::insert(self, index, element).map_err(CapacityError::new)
+    }
+
+    fn remove(&mut self, index: usize) -> Self::Item {
+        heapless::Vec::remove(self, index)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        heapless::Vec::truncate(self, len)
+    }
+}
+
+#[cfg(feature = "tinyvec")]
+impl<A> SortedStorage for tinyvec::TinyVec<A>
+where
+    A: tinyvec::Array,
+{
+    type Item = A::Item;
+    type Error = TryReserveError;
+
+    fn as_slice(&self) -> &[Self::Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [Self::Item] {
+        self
+    }
+
+    fn insert(&mut self, index: usize, element: Self::Item) {
+        tinyvec::TinyVec::insert(self, index, element)
+    }
+
+    fn try_insert(&mut self, index: usize, element: Self::Item) -> Result<(), Self::Error> {
+        self.try_reserve(1)?;
+        tinyvec::TinyVec::insert(self, index, element);
+        Ok(())
+    }
+
+    fn remove(&mut self, index: usize) -> Self::Item {
+        tinyvec::TinyVec::remove(self, index)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        tinyvec::TinyVec::truncate(self, len)
+    }
+}
@@ -0,0 +1,262 @@
+use core::fmt::{self, Debug};
+use core::ops::Deref;
+use core::slice;
+
+use smallvec::Array;
+
+use crate::SmallOrdSet;
+
+/// Which element a bounded set evicts when it is full.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Evict {
+    /// Evict the largest element, keeping the smallest elements.
+    Largest,
+    /// Evict the smallest element, keeping the largest elements.
+    Smallest,
+}
+
+/// A set holding at most a fixed number of the best elements inserted into it, represented by a
+/// sorted `SmallVec`.
+///
+/// When the set is full, inserting an element evicts either the largest or the smallest element,
+/// as configured by [`Evict`](enum.Evict.html). Elements which would be evicted immediately are
+/// rejected after a single comparison with the worst element in the set.
+///
+/// # Examples
+///
+/// ```
+/// use small_ord_set::{Evict, SmallTopKSet};
+///
+/// let mut best = SmallTopKSet::<[u32; 3]>::with_eviction(3, Evict::Smallest);
+///
+/// for score in [5, 1, 9, 7, 3] {
+///     best.insert(score);
+/// }
+///
+/// assert_eq!(best.as_slice(), &[5, 7, 9]);
+/// assert_eq!(best.insert(2), Some(2));
+/// assert_eq!(best.insert(8), Some(5));
+/// ```
+pub struct SmallTopKSet<A: Array> {
+    set: SmallOrdSet<A>,
+    max_len: usize,
+    evict: Evict,
+}
+
+impl<A> SmallOrdSet<A>
+where
+    A: Array,
+    A::Item: Ord,
+{
+    /// Adds an element to the set, keeping at most `max_len` of the smallest elements.
+    ///
+    /// If the set is full, the largest element is evicted and returned.
+    ///
+    /// If the element is not smaller than the largest element of a full set, or is already
+    /// present, it is returned without modifying the set. In both cases the returned element is
+    /// the one passed in, so the two cannot be told apart by the return value alone; use
+    /// [`contains`](#method.contains) beforehand if the distinction matters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set already holds more than `max_len` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::from_buf([1, 3, 5]);
+    ///
+    /// assert_eq!(set.insert_bounded(2, 3), Some(5));
+    /// assert_eq!(set.insert_bounded(4, 3), Some(4));
+    /// assert_eq!(set.insert_bounded(2, 3), Some(2));
+    /// assert_eq!(set.as_slice(), &[1, 2, 3]);
+    /// ```
+    ///
+    /// ```should_panic
+    /// use small_ord_set::SmallOrdSet;
+    ///
+    /// let mut set = SmallOrdSet::from_buf([1, 2, 3, 4, 5]);
+    ///
+    /// set.insert_bounded(0, 3);
+    /// ```
+    pub fn insert_bounded(&mut self, element: A::Item, max_len: usize) -> Option<A::Item> {
+        self.insert_evicting(element, max_len, Evict::Largest)
+    }
+
+    fn insert_evicting(
+        &mut self,
+        element: A::Item,
+        max_len: usize,
+        evict: Evict,
+    ) -> Option<A::Item> {
+        assert!(
+            self.len() <= max_len,
+            "set holds more than `max_len` elements"
+        );
+
+        if self.len() >= max_len {
+            let worst = match evict {
+                Evict::Largest => self.last().filter(|last| element < **last),
+                Evict::Smallest => self.first().filter(|first| element > **first),
+            };
+            if worst.is_none() {
+                return Some(element);
+            }
+        }
+
+        let idx = match self.find(&element) {
+            Ok(_) => return Some(element),
+            Err(idx) => idx,
+        };
+        self.storage.insert(idx, element);
+        if self.len() > max_len {
+            match evict {
+                Evict::Largest => self.storage.pop(),
+                Evict::Smallest => Some(self.storage.remove(0)),
+            }
+        } else {
+            None
+        }
+    }
+}
+
+impl<A: Array> SmallTopKSet<A> {
+    /// Make a new, empty, `SmallTopKSet` holding at most `max_len` elements, which evicts the
+    /// largest element when full.
+    pub fn new(max_len: usize) -> Self {
+        SmallTopKSet::with_eviction(max_len, Evict::Largest)
+    }
+
+    /// Make a new, empty, `SmallTopKSet` holding at most `max_len` elements, which evicts
+    /// elements according to `evict` when full.
+    pub fn with_eviction(max_len: usize, evict: Evict) -> Self {
+        SmallTopKSet {
+            set: SmallOrdSet::new(),
+            max_len,
+            evict,
+        }
+    }
+
+    /// The maximum number of elements the set can hold.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Which element the set evicts when it is full.
+    pub fn eviction(&self) -> Evict {
+        self.evict
+    }
+
+    /// Get a reference to the underlying set.
+    pub fn as_set(&self) -> &SmallOrdSet<A> {
+        &self.set
+    }
+
+    /// Convert into the underlying set.
+    pub fn into_set(self) -> SmallOrdSet<A> {
+        self.set
+    }
+
+    /// Get a slice containing the whole set in sorted order.
+    pub fn as_slice(&self) -> &[A::Item] {
+        self.set.as_slice()
+    }
+
+    /// Remove all elements from the set.
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    /// The number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Returns `true` if the set holds its maximum number of elements.
+    pub fn is_full(&self) -> bool {
+        self.set.len() >= self.max_len
+    }
+
+    /// Construct an iterator over the set, in ascending order.
+    pub fn iter(&self) -> slice::Iter<'_, A::Item> {
+        self.set.iter()
+    }
+}
+
+impl<A> SmallTopKSet<A>
+where
+    A: Array,
+    A::Item: Ord,
+{
+    /// Adds an element to the set.
+    ///
+    /// If the set is full, the worst element is evicted and returned. If the element is not
+    /// better than the worst element of a full set, or is already present, it is returned
+    /// without modifying the set; the return value does not distinguish these two cases.
+    pub fn insert(&mut self, element: A::Item) -> Option<A::Item> {
+        self.set.insert_evicting(element, self.max_len, self.evict)
+    }
+}
+
+impl<A> Clone for SmallTopKSet<A>
+where
+    A: Array,
+    A::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        SmallTopKSet {
+            set: self.set.clone(),
+            max_len: self.max_len,
+            evict: self.evict,
+        }
+    }
+}
+
+impl<A> Debug for SmallTopKSet<A>
+where
+    A: Array,
+    A::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.set, f)
+    }
+}
+
+impl<A: Array> Deref for SmallTopKSet<A> {
+    type Target = [A::Item];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<A> Extend<A::Item> for SmallTopKSet<A>
+where
+    A: Array,
+    A::Item: Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = A::Item>,
+    {
+        for element in iter {
+            self.insert(element);
+        }
+    }
+}
+
+impl<'a, A: Array> IntoIterator for &'a SmallTopKSet<A> {
+    type IntoIter = slice::Iter<'a, A::Item>;
+    type Item = &'a A::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt::Debug;
+use std::ops::Bound;
+
+use small_ord_set::{CapacityError, InlineStorage, KeyValuePair, SortedSet, SortedStorage};
+
+fn check_insertion_order<S>()
+where
+    S: SortedStorage<Item = u32> + Default,
+    S::Error: Debug,
+{
+    let mut set = SortedSet::<S>::new();
+    assert!(set.insert(3));
+    assert!(set.insert(1));
+    assert!(!set.insert(3));
+    assert!(set.try_insert(2).unwrap());
+    assert!(!set.try_insert(1).unwrap());
+    assert_eq!(set.as_slice(), &[1, 2, 3]);
+
+    assert_eq!(set.remove(&2), Some(2));
+    assert_eq!(set.as_slice(), &[1, 3]);
+}
+
+fn check_entry_and_cursor<S, M>()
+where
+    S: SortedStorage<Item = u32> + Default,
+    M: SortedStorage<Item = KeyValuePair<u32, u32>> + Default,
+{
+    let mut map = SortedSet::<M>::new();
+    for key in [2, 1, 2, 3, 2] {
+        *map.entry(key).or_insert(0) += 1;
+    }
+    map.entry(3).and_modify(|value| *value += 10);
+    assert_eq!(map.get_value(&1), Some(&1));
+    assert_eq!(map.get_value(&2), Some(&3));
+    assert_eq!(map.get_value(&3), Some(&11));
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+
+    let mut set = SortedSet::<S>::new();
+    set.insert(1);
+    set.insert(4);
+    let mut cursor = set.lower_bound_mut(Bound::Included(&4));
+    assert!(cursor.insert_before(2).is_ok());
+    assert!(cursor.insert_before(5).is_err());
+    assert!(cursor.insert_after(5).is_ok());
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), Some(2));
+    assert_eq!(set.as_slice(), &[1, 4, 5]);
+}
+
+fn check_full_capacity<S>()
+where
+    S: SortedStorage<Item = u32, Error = CapacityError<u32>> + Default,
+{
+    let mut set = SortedSet::<S>::new();
+    assert_eq!(set.try_insert(2), Ok(true));
+    assert_eq!(set.try_insert(3), Ok(true));
+    assert_eq!(set.try_insert(1), Ok(true));
+    assert_eq!(set.try_insert(2), Ok(false));
+    assert_eq!(set.try_insert(4).unwrap_err().into_element(), 4);
+    assert_eq!(set.try_replace(3), Ok(Some(3)));
+    assert_eq!(set.try_replace(0).unwrap_err().into_element(), 0);
+    assert_eq!(set.as_slice(), &[1, 2, 3]);
+}
+
+fn check_full_entry_panics<M>()
+where
+    M: SortedStorage<Item = KeyValuePair<u32, u32>> + Default,
+{
+    let mut map = SortedSet::<M>::new();
+    for key in 0..3 {
+        map.entry(key).or_insert(key);
+    }
+    map.entry(3).or_insert(3);
+}
+
+#[test]
+fn vec() {
+    check_insertion_order::<Vec<u32>>();
+    check_entry_and_cursor::<Vec<u32>, Vec<KeyValuePair<u32, u32>>>();
+}
+
+#[test]
+fn inline_storage() {
+    check_insertion_order::<InlineStorage<[u32; 3]>>();
+    check_entry_and_cursor::<InlineStorage<[u32; 4]>, InlineStorage<[KeyValuePair<u32, u32>; 3]>>();
+    check_full_capacity::<InlineStorage<[u32; 3]>>();
+}
+
+#[test]
+#[should_panic(expected = "insufficient capacity")]
+fn inline_storage_full_entry() {
+    check_full_entry_panics::<InlineStorage<[KeyValuePair<u32, u32>; 3]>>();
+}
+
+#[cfg(feature = "arrayvec")]
+mod array_vec {
+    use arrayvec::ArrayVec;
+
+    use super::*;
+
+    #[test]
+    fn array_vec() {
+        check_insertion_order::<ArrayVec<u32, 3>>();
+        check_entry_and_cursor::<ArrayVec<u32, 4>, ArrayVec<KeyValuePair<u32, u32>, 3>>();
+        check_full_capacity::<ArrayVec<u32, 3>>();
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient capacity")]
+    fn array_vec_full_entry() {
+        check_full_entry_panics::<ArrayVec<KeyValuePair<u32, u32>, 3>>();
+    }
+}
+
+#[cfg(feature = "heapless")]
+mod heapless_vec {
+    use heapless::Vec;
+
+    use super::*;
+
+    #[test]
+    fn heapless_vec() {
+        check_insertion_order::<Vec<u32, 3>>();
+        check_entry_and_cursor::<Vec<u32, 4>, Vec<KeyValuePair<u32, u32>, 3>>();
+        check_full_capacity::<Vec<u32, 3>>();
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient capacity")]
+    fn heapless_vec_full_entry() {
+        check_full_entry_panics::<Vec<KeyValuePair<u32, u32>, 3>>();
+    }
+}
+
+#[cfg(feature = "tinyvec")]
+mod tiny_vec {
+    use tinyvec::TinyVec;
+
+    use super::*;
+
+    #[test]
+    fn tiny_vec() {
+        check_insertion_order::<TinyVec<[u32; 2]>>();
+        check_entry_and_cursor::<TinyVec<[u32; 2]>, TinyVec<[KeyValuePair<u32, u32>; 2]>>();
+
+        let mut set = SortedSet::<TinyVec<[u32; 2]>>::new();
+        set.extend([3, 1, 2]);
+        assert!(set.as_storage().is_heap());
+        assert_eq!(set.as_slice(), &[1, 2, 3]);
+    }
+}